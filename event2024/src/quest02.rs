@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use utils::grid::{BitGrid, Direction, Grid, Wrap};
 
 pub fn part1(input: &str) -> u32 {
     let (words, lines) = parse(input);
@@ -19,63 +19,20 @@ pub fn part2(input: &str) -> u32 {
 pub fn part3(input: &str) -> u32 {
     let (words, lines) = parse(input);
 
-    // add reversed words to we don't have to check both directions later
-    let words = words
-        .iter()
-        .map(|&w| w.to_string())
-        .chain(words.iter().map(|&w| w.chars().rev().collect()))
-        .collect::<HashSet<_>>();
-
-    let grid: Vec<Vec<char>> = lines.iter().map(|&l| l.chars().collect()).collect();
-
-    let line_width = grid.iter().map(|r| r.len()).max().unwrap();
-    let widest_word = words.iter().map(|w| w.len()).max().unwrap();
-
-    let mut marked: Vec<Vec<bool>> = vec![vec![false; line_width]; grid.len()];
-    let mut slice = String::with_capacity(widest_word);
+    let grid = Grid::from_rows_padded(lines.iter().map(|&l| l.chars().collect()).collect(), ' ');
+    let mut marked = BitGrid::new(grid.width(), grid.height());
 
-    for y in 0..grid.len() {
-        for x in 0..line_width {
-            slice.clear();
-
-            // try right first
-            for dx in 0..widest_word {
-                let c = grid[y][(x + dx) % line_width];
-                slice.push(c);
-
-                if words.contains(&slice) {
-                    for mx in 0..=dx {
-                        marked[y][(x + mx) % line_width] = true;
-                    }
-                }
-            }
-
-            slice.clear();
-
-            // try down
-            for dy in 0..widest_word {
-                if y + dy >= grid.len() {
-                    // fell off the bottom
-                    break;
-                }
-
-                let c = grid[y + dy][x];
-                slice.push(c);
-
-                if words.contains(&slice) {
-                    for my in 0..=dy {
-                        marked[y + my][x] = true;
-                    }
-                }
+    // words can read either way along a row, wrapping around the edge of the
+    // scales, or either way down a column
+    for word in words {
+        for found in grid.find_word(word, &Direction::CARDINAL, Wrap::Horizontal) {
+            for p in found.cells {
+                marked.insert(p);
             }
         }
     }
 
-    // count marked cells
-    marked
-        .iter()
-        .map(|row| row.iter().filter(|&&b| b).count() as u32)
-        .sum()
+    marked.count_ones() as u32
 }
 
 fn parse(input: &str) -> (Vec<&str>, Vec<&str>) {
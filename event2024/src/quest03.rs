@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use utils::grid::{Grid, Neighbourhood};
 
 #[rustfmt::skip]
 pub const DELTAS: [(i32, i32); 4] = [
@@ -15,40 +15,14 @@ pub const DELTAS_WITH_DIAGONALS: [(i32, i32); 8] = [
 ];
 
 pub fn solve(input: &str, deltas: &[(i32, i32)]) -> u32 {
-    let mut excavations = HashMap::new();
+    let grid = Grid::parse_padded(input, '.');
 
-    for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            if c == '#' {
-                // we can always excavate the entire first layer
-                excavations.insert((x as i32, y as i32), 1);
-            }
-        }
-    }
-
-    let mut count = excavations.len() as u32;
-
-    // keep digging until we can't dig any more
-    while !excavations.is_empty() {
-        let mut new_excavations = HashMap::new();
-
-        for (&(x, y), &depth) in excavations.iter() {
-            let same_depth = deltas
-                .iter()
-                .filter(|&d| *excavations.get(&(x + d.0, y + d.1)).unwrap_or(&0) == depth)
-                .count();
-
-            // we can excavate this square if every adjacent square is the same depth
-            if same_depth == deltas.len() {
-                new_excavations.insert((x, y), depth + 1);
-            }
-        }
-
-        count += new_excavations.len() as u32;
-        excavations = new_excavations;
-    }
-
-    count
+    // each block can be dug out to the same depth as its distance from the
+    // nearest open ground, so the total is the sum of all those distances
+    grid.distance_transform(Neighbourhood::Custom(deltas), |&c| c == '#')
+        .rows()
+        .flatten()
+        .sum()
 }
 
 #[cfg(test)]
@@ -58,6 +32,42 @@ mod tests {
 
     const QUEST: u32 = 3;
 
+    const EXAMPLE: &str = "..........
+..###.##..
+...####...
+..######..
+..######..
+...####...
+..........";
+
+    #[test]
+    fn test_part1_example() {
+        assert_eq!(solve(EXAMPLE, &DELTAS), 35);
+    }
+
+    #[test]
+    fn test_example_depths() {
+        let grid = Grid::parse_padded(EXAMPLE, '.');
+        let depths = grid.distance_transform(Neighbourhood::Custom(&DELTAS), |&c| c == '#');
+
+        let expected = Grid::from_rows(
+            [
+                "0000000000",
+                "0011101100",
+                "0001211000",
+                "0012322100",
+                "0012222100",
+                "0001111000",
+                "0000000000",
+            ]
+            .iter()
+            .map(|row| row.chars().map(|c| c.to_digit(10).unwrap()).collect())
+            .collect(),
+        );
+
+        utils::assert_grid_eq!(depths, expected, |_, &d| char::from_digit(d, 10).unwrap());
+    }
+
     #[test]
     fn test_part1_real() {
         let input = utils::load_event_input(EVENT, QUEST, 1);
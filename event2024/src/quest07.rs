@@ -1,5 +1,6 @@
 use rayon::prelude::*;
-use std::collections::{BTreeMap, HashSet};
+use std::collections::BTreeMap;
+use utils::grid::{Direction, Grid};
 
 pub fn part1(input: &str) -> String {
     let plans = input
@@ -103,46 +104,25 @@ pub fn part3(input: &str, track: &str) -> u32 {
 /// Extract the track loop from the given track string
 ///
 /// The track could be completely jagged (see part 3), thus we need to follow it
-/// around to extract the loop from (1, 0) until we reach the S node
+/// around from the S node, heading right, until we arrive back at S
 fn get_track_loop(track: &str) -> String {
-    const DIRECTIONS: &[(isize, isize)] = &[(1, 0), (0, 1), (0, -1), (-1, 0)]; // RDUL
-
-    // collect to a grid of char first to make indexing easier
-    let grid: Vec<Vec<char>> = track.lines().map(|line| line.chars().collect()).collect();
-    let height = grid.len() as isize;
-    let width = grid[0].len() as isize;
-
-    let mut path = String::new();
-    let mut visited = HashSet::new();
-    let mut current = grid[0][1];
-    let mut x = 1_isize;
-    let mut y = 0_isize;
-
-    while current != 'S' {
-        path.push(current);
-        visited.insert((x, y));
-
-        for &(dx, dy) in DIRECTIONS {
-            let nx = x + dx;
-            let ny = y + dy;
-
-            if ny >= 0
-                && ny < height
-                && nx >= 0
-                && nx < width
-                && grid[ny as usize][nx as usize] != ' '
-                && !visited.contains(&(nx, ny))
-            {
-                current = grid[ny as usize][nx as usize];
-                x = nx;
-                y = ny;
-                break;
-            }
-        }
-    }
-
-    path.push('S');
-    path
+    // pad any short lines so the track can be indexed as a rectangle
+    let grid = Grid::parse_padded(track, ' ');
+    let start = grid.find(&'S').expect("Track has no start");
+
+    let trace = grid
+        .trace_path(start, |&c| c != ' ', Some(Direction::East))
+        .expect("Track should be a single loop");
+    assert!(trace.closed, "Track doesn't loop back to S");
+
+    // the loop is scored from the first step after S, finishing back on S
+    trace
+        .cells
+        .iter()
+        .skip(1)
+        .chain(trace.cells.first())
+        .copied()
+        .collect()
 }
 
 /// Score the given instructions on the track loop for the specified number of rounds
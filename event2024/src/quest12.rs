@@ -1,3 +1,5 @@
+use utils::grid::Point2;
+
 pub fn part1(input: &str) -> u32 {
     part2(input)
 }
@@ -30,24 +32,28 @@ pub fn part2(input: &str) -> u32 {
         .sum()
 }
 
-pub fn part3(input: &str) -> i32 {
+pub fn part3(input: &str) -> i64 {
     // basically stole this one from the solution thread
     input
         .lines()
         .map(|line| {
-            let split = line.split_once(' ').unwrap();
-            (
-                split.0.parse::<i32>().unwrap(),
-                split.1.parse::<i32>().unwrap(),
-            )
+            let (x, y) = line.split_once(' ').unwrap();
+            Point2::new(x.parse::<i64>().unwrap(), y.parse::<i64>().unwrap())
+        })
+        .map(|meteor| {
+            intercept(Point2::new(
+                meteor.x / 2,
+                meteor.y - (meteor.x / 2) - (meteor.x % 2),
+            ))
         })
-        .map(|(x, y)| intercept(x / 2, y - (x / 2) - (x % 2)))
         .sum()
 }
 
-fn intercept(x: i32, y: i32) -> i32 {
+fn intercept(target: Point2<i64>) -> i64 {
+    let x = target.x;
+
     for start in 0..3 {
-        let y = y - start;
+        let y = target.y - start;
 
         if x < y {
             continue;
@@ -69,9 +75,41 @@ fn intercept(x: i32, y: i32) -> i32 {
 mod tests {
     use super::*;
     use crate::EVENT;
+    use utils::grid::{Grid, Point, Trajectory};
 
     const QUEST: u32 = 12;
 
+    /// Fire every catapult segment at every power and keep the lowest ranking
+    /// shot which hits each target, to check the closed form against
+    fn simulate(input: &str) -> u32 {
+        let grid: Grid<char> = input.parse().unwrap();
+        let ground = grid.height() as i32 - 1;
+
+        grid.find_all(|&c| c == 'T' || c == 'H')
+            .map(|target| {
+                let ranking = (0..3)
+                    .flat_map(|segment| {
+                        let start = Point::new(1, ground - 1 - segment);
+
+                        (1..=target.x as u32)
+                            .filter(move |&power| {
+                                Trajectory::catapult(start, power)
+                                    .take_while(|p| p.x <= target.x)
+                                    .any(|p| p == target)
+                            })
+                            .map(move |power| (segment as u32 + 1) * power)
+                    })
+                    .min()
+                    .expect("Target can't be hit");
+
+                match grid.at(target) {
+                    Some('H') => 2 * ranking,
+                    _ => ranking,
+                }
+            })
+            .sum()
+    }
+
     #[test]
     fn test_part1_real() {
         let input = utils::load_event_input(EVENT, QUEST, 1);
@@ -92,6 +130,7 @@ mod tests {
         .to_string();
 
         assert_eq!(part1(&input), 13);
+        assert_eq!(simulate(&input), 13);
     }
 
     #[test]
@@ -100,6 +139,12 @@ mod tests {
         assert_eq!(part2(&input), 21994);
     }
 
+    #[test]
+    fn test_part2_simulated() {
+        let input = utils::load_event_input(EVENT, QUEST, 2);
+        assert_eq!(simulate(&input), part2(&input));
+    }
+
     #[test]
     fn test_part3_real() {
         let input = utils::load_event_input(EVENT, QUEST, 3);
@@ -2,37 +2,12 @@ use std::collections::{HashSet, VecDeque};
 use utils::{graph::Graph, grid::*};
 
 pub fn part1_and_2(input: &str) -> u32 {
-    let grid = parse_grid(input);
-
-    let start = input
-        .lines()
-        .enumerate()
-        .find_map(|(y, line)| {
-            line.chars().enumerate().find_map(|(x, c)| {
-                if c == 'S' {
-                    Some(Point::new(x as i32, y as i32))
-                } else {
-                    None
-                }
-            })
-        })
-        .unwrap();
-
-    let target = input
-        .lines()
-        .enumerate()
-        .find_map(|(y, line)| {
-            line.chars().enumerate().find_map(|(x, c)| {
-                if c == 'E' {
-                    Some(Point::new(x as i32, y as i32))
-                } else {
-                    None
-                }
-            })
-        })
-        .unwrap();
+    let markers: Grid<char> = input.parse().unwrap();
+
+    let start = markers.find(&'S').unwrap();
+    let target = markers.find(&'E').unwrap();
 
-    let graph = build_graph(&grid, start);
+    let graph = build_graph(&heights(&markers), start);
 
     graph
         .dijkstra(start, target)
@@ -40,51 +15,27 @@ pub fn part1_and_2(input: &str) -> u32 {
 }
 
 pub fn part3(input: &str) -> u32 {
-    let grid = parse_grid(input);
-
-    let start: Vec<Point> = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| {
-            line.chars().enumerate().filter_map(move |(x, c)| {
-                if c == 'S' {
-                    Some(Point::new(x as i32, y as i32))
-                } else {
-                    None
-                }
-            })
-        })
-        .collect();
-
-    let target = input
-        .lines()
-        .enumerate()
-        .find_map(|(y, line)| {
-            line.chars().enumerate().find_map(|(x, c)| {
-                if c == 'E' {
-                    Some(Point::new(x as i32, y as i32))
-                } else {
-                    None
-                }
-            })
-        })
-        .unwrap();
+    let markers: Grid<char> = input.parse().unwrap();
+
+    let start: Vec<Point> = markers.find_all(|&c| c == 'S').collect();
+    let target = markers.find(&'E').unwrap();
 
-    let graph = build_graph(&grid, *start.first().unwrap());
+    let graph = build_graph(&heights(&markers), *start.first().unwrap());
 
     graph.dijkstra_many(&start, target).expect("No paths found") as u32
 }
 
-fn parse_grid(input: &str) -> Grid<Option<u32>> {
+/// The height of each cell, or `None` for walls. The start and end are at
+/// height 0.
+fn heights(markers: &Grid<char>) -> Grid<Option<u32>> {
     Grid::from_rows(
-        input
-            .lines()
-            .map(|line| {
-                line.bytes()
-                    .map(|b| match b {
-                        b'0'..=b'9' => Some((b - b'0') as u32),
-                        b'S' => Some(0),
-                        b'E' => Some(0),
+        markers
+            .rows()
+            .map(|row| {
+                row.iter()
+                    .map(|&c| match c {
+                        '0'..='9' => c.to_digit(10),
+                        'S' | 'E' => Some(0),
                         _ => None,
                     })
                     .collect()
@@ -110,8 +61,8 @@ fn build_graph(grid: &Grid<Option<u32>>, start: Point) -> Graph {
             .expect("Somehow navigated out of bounds")
             .expect("Somehow navigated into a wall");
 
-        for neighbour in current.neighbours4() {
-            if let Some(Some(next)) = grid.at(neighbour) {
+        for (neighbour, cell) in grid.neighbours4(current) {
+            if let Some(next) = cell {
                 let height_diff = ((height + 10 - next) % 10).min((next + 10 - height) % 10);
 
                 graph.add_edge(current, neighbour, height_diff as usize + 1);
@@ -1,47 +1,9 @@
-use core::panic;
 use std::{
     collections::{HashSet, VecDeque},
     str::FromStr,
     string::ParseError,
 };
-use utils::grid::Point3D;
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Direction3D {
-    Left,
-    Right,
-    Up,
-    Down,
-    Forwards,
-    Backwards,
-}
-
-impl Direction3D {
-    fn delta(&self) -> Point3D {
-        match self {
-            Direction3D::Left => Point3D { x: -1, y: 0, z: 0 },
-            Direction3D::Right => Point3D { x: 1, y: 0, z: 0 },
-            Direction3D::Up => Point3D { x: 0, y: 1, z: 0 },
-            Direction3D::Down => Point3D { x: 0, y: -1, z: 0 },
-            Direction3D::Forwards => Point3D { x: 0, y: 0, z: 1 },
-            Direction3D::Backwards => Point3D { x: 0, y: 0, z: -1 },
-        }
-    }
-}
-
-impl From<char> for Direction3D {
-    fn from(value: char) -> Self {
-        match value {
-            'L' => Direction3D::Left,
-            'R' => Direction3D::Right,
-            'U' => Direction3D::Up,
-            'D' => Direction3D::Down,
-            'F' => Direction3D::Forwards,
-            'B' => Direction3D::Backwards,
-            _ => panic!("Unexpected char"),
-        }
-    }
-}
+use utils::grid::{Direction3D, Grid3D, Point3D};
 
 #[derive(Clone, Copy, Debug)]
 struct Instruction {
@@ -53,7 +15,7 @@ impl FromStr for Instruction {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let direction = s.chars().next().unwrap().into();
+        let direction = s[..1].parse().unwrap();
         let steps = s[1..].parse().unwrap();
 
         Ok(Instruction { direction, steps })
@@ -61,7 +23,7 @@ impl FromStr for Instruction {
 }
 
 pub fn part1(input: &str) -> u32 {
-    let mut point = Point3D { x: 0, y: 0, z: 0 };
+    let mut point = Point3D::zero();
     let mut highest = 0;
 
     for instruction in input
@@ -82,7 +44,7 @@ pub fn part2(input: &str) -> usize {
     let mut segments = HashSet::new();
 
     for line in input.lines() {
-        let mut point = Point3D { x: 0, y: 0, z: 0 };
+        let mut point = Point3D::zero();
 
         for instruction in line
             .split(',')
@@ -104,7 +66,7 @@ pub fn part3(input: &str) -> u32 {
     let mut segments = HashSet::new();
 
     for line in input.lines() {
-        let mut point = Point3D { x: 0, y: 0, z: 0 };
+        let mut point = Point3D::zero();
 
         for instruction in line
             .split(',')
@@ -123,6 +85,20 @@ pub fn part3(input: &str) -> u32 {
         leaves.insert(point);
     }
 
+    // pack the branches into a dense voxel grid for fast lookups
+    let min = segments.iter().fold(Point3D::zero(), |m, p| {
+        Point3D::new(m.x.min(p.x), m.y.min(p.y), m.z.min(p.z))
+    });
+    let max = segments.iter().fold(Point3D::zero(), |m, p| {
+        Point3D::new(m.x.max(p.x), m.y.max(p.y), m.z.max(p.z))
+    });
+
+    let mut tree = Grid3D::with_bounds(min, max, false);
+
+    for &segment in segments.iter() {
+        *tree.at_mut(segment).unwrap() = true;
+    }
+
     let mut best = u32::MAX;
 
     for t in trunk {
@@ -143,8 +119,8 @@ pub fn part3(input: &str) -> u32 {
                 continue;
             }
 
-            for next in current.neighbours6() {
-                if segments.contains(&next) {
+            for (next, &is_segment) in tree.neighbours6(current) {
+                if is_segment {
                     queue.push_back((distance + 1, next));
                 }
             }
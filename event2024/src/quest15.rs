@@ -1,5 +1,5 @@
 use std::collections::{HashSet, VecDeque};
-use utils::grid::{Grid, Point};
+use utils::grid::Grid;
 
 pub fn part1(input: &str) -> u32 {
     solve(input)
@@ -24,14 +24,8 @@ fn solve(input: &str) -> u32 {
         .iter()
         .fold(0u32, |acc, &t| acc + (1 << (*t as u8 - b'A')));
 
-    let start_x = grid
-        .rows()
-        .next()
-        .unwrap()
-        .iter()
-        .position(|&c| c == '.')
-        .unwrap() as i32;
-    let start = Point::new(start_x, 0);
+    // the entrance is the first open cell in the top row
+    let start = grid.find(&'.').unwrap();
 
     let mut queue = VecDeque::new();
     let mut seen = HashSet::new();
@@ -62,11 +56,7 @@ fn solve(input: &str) -> u32 {
             most_collected = most_collected.max(collected.count_ones());
         }
 
-        for neighbour in current.neighbours4() {
-            let Some(&cell) = grid.at(neighbour) else {
-                continue;
-            };
-
+        for (neighbour, &cell) in grid.neighbours4(current) {
             if cell == '.' || cell.is_alphabetic() {
                 queue.push_back((distance + 1, neighbour, collected));
             }
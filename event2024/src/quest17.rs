@@ -1,18 +1,13 @@
-use std::{
-    cmp::Reverse,
-    collections::{BinaryHeap, HashSet},
-};
+use std::{cmp::Reverse, collections::BinaryHeap};
 use utils::grid::Point;
+use utils::metric::{Manhattan, Metric};
+use utils::spatial::SpatialIndex;
 
 pub fn part1(input: &str) -> usize {
-    let stars = parse(input);
-
-    constellation_size(
-        &stars,
-        *stars.iter().next().unwrap(),
-        &mut HashSet::new(),
-        usize::MAX,
-    )
+    let mut stars = parse(input);
+    let start = stars.iter().next().unwrap();
+
+    constellation_size(&mut stars, start, u64::MAX, Manhattan)
 }
 
 pub fn part2(input: &str) -> usize {
@@ -20,16 +15,13 @@ pub fn part2(input: &str) -> usize {
 }
 
 pub fn part3(input: &str) -> usize {
-    let stars = parse(input);
-
-    let mut seen = HashSet::new();
+    let mut stars = parse(input);
     let mut constellation_sizes = vec![];
 
-    while seen.len() < stars.len() {
-        // try to start a new constellation
-        let start = stars.iter().find(|&s| !seen.contains(s)).unwrap();
-
-        let size = constellation_size(&stars, *start, &mut seen, 6);
+    // start a new constellation from any star not yet in one
+    while !stars.is_empty() {
+        let start = stars.iter().next().unwrap();
+        let size = constellation_size(&mut stars, start, 6, Manhattan);
         constellation_sizes.push(size);
     }
 
@@ -37,62 +29,51 @@ pub fn part3(input: &str) -> usize {
     constellation_sizes.iter().rev().take(3).product()
 }
 
-fn parse(input: &str) -> HashSet<Point> {
-    input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| {
-            line.char_indices().filter_map(move |(x, c)| {
-                if c == '*' {
-                    Some(Point::new(x as i32 + 1, y as i32 + 1))
-                } else {
-                    None
-                }
-            })
-        })
-        .collect()
+/// Index the stars in buckets the size of the distance limit for constellations
+fn parse(input: &str) -> SpatialIndex<Point> {
+    let mut stars = SpatialIndex::new(6);
+
+    for (y, line) in input.lines().enumerate() {
+        for (x, c) in line.char_indices() {
+            if c == '*' {
+                stars.insert(Point::new(x as i32 + 1, y as i32 + 1));
+            }
+        }
+    }
+
+    stars
 }
 
 /// Build the minimum spanning tree like Prim's algorithm, except we only need to sum the weights
-fn constellation_size(
-    stars: &HashSet<Point>,
+///
+/// Stars are joined when they're closer than `limit` as measured by `metric`, and are removed
+/// from `unseen` as they're added to the constellation
+fn constellation_size<M: Metric<Point> + Copy>(
+    unseen: &mut SpatialIndex<Point>,
     start: Point,
-    seen: &mut HashSet<Point>,
-    limit: usize,
+    limit: u64,
+    metric: M,
 ) -> usize {
-    seen.insert(start);
+    unseen.remove(start);
 
     let mut min_heap = BinaryHeap::new();
     let mut constellation_weight = 0_usize;
     let mut constellation_stars = 1_usize;
 
-    for next in stars.iter().filter(|&p| !seen.contains(p)) {
-        let distance = next.manhattan_distance(start) as usize;
-
-        if distance >= limit {
-            continue;
-        }
-
-        min_heap.push(Reverse((distance, next, start)));
+    for next in unseen.within(start, limit - 1, metric) {
+        min_heap.push(Reverse((metric.distance(next, start), next)));
     }
 
-    while let Some(Reverse((cost, next, _))) = min_heap.pop() {
-        if !seen.insert(*next) {
+    while let Some(Reverse((cost, next))) = min_heap.pop() {
+        if !unseen.remove(next) {
             continue;
         }
 
-        constellation_weight += cost;
+        constellation_weight += cost as usize;
         constellation_stars += 1;
 
-        for other in stars.iter().filter(|&p| !seen.contains(p)) {
-            let distance = other.manhattan_distance(*next) as usize;
-
-            if distance >= limit {
-                // not part of this constellation
-                continue;
-            }
-
-            min_heap.push(Reverse((distance, other, *next)));
+        for other in unseen.within(next, limit - 1, metric) {
+            min_heap.push(Reverse((metric.distance(other, next), other)));
         }
     }
 
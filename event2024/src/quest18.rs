@@ -1,36 +1,20 @@
-use rayon::prelude::*;
-use std::collections::{HashSet, VecDeque};
-use utils::grid::Point;
+use utils::grid::{BitGrid, Grid, Point};
 
 pub fn part1(input: &str) -> usize {
-    let (start, points, mut trees) = parse(input);
+    let (start, open, trees) = parse(input);
+    let mut last_watered = 0;
 
-    let mut queue = VecDeque::new();
-    let mut seen = HashSet::new();
-
-    for s in start {
-        queue.push_back((s, 0));
-        seen.insert(s);
-    }
-
-    while let Some((current, distance)) = queue.pop_front() {
-        if trees.contains(&current) {
-            trees.remove(&current);
-
-            if trees.is_empty() {
-                return distance;
-            }
+    let water = irrigate(start, &open, |p, time| {
+        if trees.contains(p) {
+            last_watered = time;
         }
+    });
 
-        for neighbour in current.neighbours4() {
-            if points.contains(&neighbour) && !seen.contains(&neighbour) {
-                seen.insert(neighbour);
-                queue.push_back((neighbour, distance + 1));
-            }
-        }
-    }
-
-    unreachable!("Not all trees could be reached")
+    assert!(
+        trees.difference(&water).is_empty(),
+        "Not all trees could be reached"
+    );
+    last_watered
 }
 
 pub fn part2(input: &str) -> usize {
@@ -38,74 +22,76 @@ pub fn part2(input: &str) -> usize {
 }
 
 pub fn part3(input: &str) -> usize {
-    let (_, points, trees) = parse(input);
+    let (_, open, trees) = parse(input);
+
+    // the time from any cell to a tree is the same as from the tree to the cell,
+    // so flood out from each tree in turn and add up the times at every cell
+    let mut time_sums = Grid::new(open.width(), open.height(), 0);
+    let mut reaches_all = open.clone();
+
+    for tree in trees.iter() {
+        let water = irrigate([tree], &open, |p, time| {
+            *time_sums.at_mut(p).unwrap() += time;
+        });
 
-    points
-        .par_iter()
-        .filter(|&p| !trees.contains(p))
-        .copied()
-        .map(|start| time_sum(start, trees.clone(), &points))
+        reaches_all &= &water;
+    }
+
+    reaches_all
+        .difference(&trees)
+        .iter()
+        .map(|p| *time_sums.at(p).unwrap())
         .min()
         .expect("At least one starting point should be possible")
 }
 
-fn time_sum(start: Point, mut trees: HashSet<Point>, points: &HashSet<Point>) -> usize {
-    let mut queue = VecDeque::new();
-    let mut seen = HashSet::new();
-    let mut time_sum = 0;
+/// Flood water out from all the start points at once, a whole step at a time,
+/// calling `watered` with each newly reached cell and the time it was reached.
+///
+/// Returns every cell which the water reached.
+fn irrigate(
+    start: impl IntoIterator<Item = Point>,
+    open: &BitGrid,
+    mut watered: impl FnMut(Point, usize),
+) -> BitGrid {
+    let mut water = BitGrid::new(open.width(), open.height());
 
-    queue.push_back((start, 0));
-    seen.insert(start);
+    for s in start {
+        water.insert(s);
+        watered(s, 0);
+    }
 
-    while let Some((current, distance)) = queue.pop_front() {
-        if trees.contains(&current) {
-            trees.remove(&current);
-            time_sum += distance;
+    for time in 1.. {
+        let next = &water.dilate4() & open;
+        let reached = &next - &water;
 
-            if trees.is_empty() {
-                return time_sum;
-            }
+        if reached.is_empty() {
+            break;
         }
 
-        for neighbour in current.neighbours4() {
-            if points.contains(&neighbour) && !seen.contains(&neighbour) {
-                seen.insert(neighbour);
-                queue.push_back((neighbour, distance + 1));
-            }
+        for p in reached.iter() {
+            watered(p, time);
         }
+
+        water = next;
     }
 
-    unreachable!("Not all trees could be reached")
+    water
 }
 
-fn parse(input: &str) -> (Vec<Point>, HashSet<Point>, HashSet<Point>) {
-    let mut points = HashSet::new();
-    let mut trees = HashSet::new();
-    let mut start = vec![];
-
-    for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.char_indices() {
-            let p = Point::new(x as i32, y as i32);
-
-            match c {
-                '.' => {
-                    points.insert(p);
-
-                    if x == 0 || y == 0 || x == line.len() - 1 || y == input.lines().count() - 1 {
-                        // on an edge
-                        start.push(p);
-                    }
-                }
-                'P' => {
-                    points.insert(p);
-                    trees.insert(p);
-                }
-                _ => {}
-            }
-        }
-    }
+fn parse(input: &str) -> (Vec<Point>, BitGrid, BitGrid) {
+    let grid: Grid<char> = input.parse().unwrap();
+
+    let open = BitGrid::from_grid(&grid, |&c| c != '#');
+    let trees = BitGrid::from_grid(&grid, |&c| c == 'P');
+
+    let start = grid
+        .bounds()
+        .border()
+        .filter(|&p| grid.at(p) == Some(&'.'))
+        .collect();
 
-    (start, points, trees)
+    (start, open, trees)
 }
 
 #[cfg(test)]
@@ -0,0 +1,210 @@
+use crate::grid::{Grid, Neighbourhood};
+use std::mem;
+
+/// Steps a `Grid` forward one generation at a time by applying a rule to every
+/// cell simultaneously.
+///
+/// The rule is given the current value of a cell and the values of its
+/// neighbours, in the order of the `Neighbourhood` deltas, with `None` for any
+/// neighbour outside the grid. Each generation is written into a second buffer
+/// so every cell sees the previous generation, then the buffers are swapped.
+pub struct Automaton<'a, T, F> {
+    current: Grid<T>,
+    next: Grid<T>,
+    neighbourhood: Neighbourhood<'a>,
+    rule: F,
+    generation: usize,
+    stable: bool,
+}
+
+impl<'a, T, F> Automaton<'a, T, F>
+where
+    T: Clone + PartialEq,
+    F: FnMut(&T, &[Option<&T>]) -> T,
+{
+    /// Create an automaton starting from `grid` at generation 0.
+    pub fn new(grid: Grid<T>, neighbourhood: Neighbourhood<'a>, rule: F) -> Self {
+        Self {
+            next: grid.clone(),
+            current: grid,
+            neighbourhood,
+            rule,
+            generation: 0,
+            stable: false,
+        }
+    }
+
+    /// The grid at the current generation.
+    pub fn grid(&self) -> &Grid<T> {
+        &self.current
+    }
+
+    /// Consume the automaton and return the grid at the current generation.
+    pub fn into_grid(self) -> Grid<T> {
+        self.current
+    }
+
+    /// Number of steps taken so far
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Returns true once a step has left every cell unchanged. Stepping a
+    /// stable automaton does nothing, so the generation stops advancing.
+    pub fn is_stable(&self) -> bool {
+        self.stable
+    }
+
+    /// Advance by a single generation, returning true if any cell changed.
+    pub fn step(&mut self) -> bool {
+        if self.stable {
+            return false;
+        }
+
+        let mut changed = false;
+        let mut neighbours = Vec::with_capacity(self.neighbourhood.deltas().len());
+
+        for (p, cell) in self.current.iter() {
+            neighbours.clear();
+            neighbours.extend(p.neighbours(self.neighbourhood).map(|n| self.current.at(n)));
+
+            let updated = (self.rule)(cell, &neighbours);
+            changed |= updated != *cell;
+            *self.next.at_mut(p).unwrap() = updated;
+        }
+
+        if changed {
+            mem::swap(&mut self.current, &mut self.next);
+            self.generation += 1;
+        } else {
+            self.stable = true;
+        }
+
+        changed
+    }
+
+    /// Keep stepping until the grid stops changing, returning the generation
+    /// at which it settled.
+    pub fn run_until_stable(&mut self) -> usize {
+        while self.step() {}
+        self.generation
+    }
+
+    /// Step until `generation` is reached or the grid stops changing, whichever
+    /// comes first. Returns true if the target generation was reached.
+    pub fn run_to(&mut self, generation: usize) -> bool {
+        while self.generation < generation {
+            if !self.step() {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Iterate over a snapshot of each new generation as it is produced,
+    /// finishing once the grid stops changing.
+    pub fn steps(&mut self) -> Steps<'_, 'a, T, F> {
+        Steps { automaton: self }
+    }
+
+    /// Collect snapshots of the current generation and up to `limit` further
+    /// generations, stopping early if the grid stops changing.
+    pub fn history(&mut self, limit: usize) -> Vec<Grid<T>> {
+        let mut history = vec![self.current.clone()];
+        history.extend(self.steps().take(limit));
+        history
+    }
+}
+
+/// Iterator returned by `Automaton::steps`.
+pub struct Steps<'s, 'a, T, F> {
+    automaton: &'s mut Automaton<'a, T, F>,
+}
+
+impl<T, F> Iterator for Steps<'_, '_, T, F>
+where
+    T: Clone + PartialEq,
+    F: FnMut(&T, &[Option<&T>]) -> T,
+{
+    type Item = Grid<T>;
+
+    fn next(&mut self) -> Option<Grid<T>> {
+        self.automaton
+            .step()
+            .then(|| self.automaton.current.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn life(cell: &char, neighbours: &[Option<&char>]) -> char {
+        let alive = neighbours.iter().filter(|n| **n == Some(&'#')).count();
+
+        match (*cell, alive) {
+            ('#', 2 | 3) | ('.', 3) => '#',
+            _ => '.',
+        }
+    }
+
+    #[test]
+    fn blinker_oscillates() {
+        let start = Grid::from_str(".....\n..#..\n..#..\n..#..\n.....").unwrap();
+        let mut automaton = Automaton::new(start.clone(), Neighbourhood::All, life);
+
+        assert!(automaton.step());
+        assert_eq!(
+            automaton.grid().to_string(),
+            ".....\n.....\n.###.\n.....\n....."
+        );
+
+        assert!(automaton.run_to(10));
+        assert_eq!(automaton.generation(), 10);
+        assert_eq!(automaton.grid(), &start);
+        assert!(!automaton.is_stable());
+    }
+
+    #[test]
+    fn block_is_stable() {
+        let start = Grid::from_str("....\n.##.\n.##.\n....").unwrap();
+        let mut automaton = Automaton::new(start.clone(), Neighbourhood::All, life);
+
+        assert_eq!(automaton.run_until_stable(), 0);
+        assert!(automaton.is_stable());
+        assert!(!automaton.run_to(5));
+        assert_eq!(automaton.into_grid(), start);
+    }
+
+    #[test]
+    fn excavation_matches_distance_transform() {
+        let map = Grid::from_str(
+            "..........\n..###.##..\n...####...\n..######..\n..######..\n...####...\n..........",
+        )
+        .unwrap();
+
+        // a block can be dug one layer deeper once every neighbour is at least as deep
+        let depths = Grid::from_rows(
+            map.rows()
+                .map(|r| r.iter().map(|&c| (c == '#') as u32).collect())
+                .collect(),
+        );
+        let mut automaton = Automaton::new(depths, Neighbourhood::Orthogonal, |&d, ns| {
+            if d > 0 && ns.iter().all(|n| n.is_some_and(|&v| v >= d)) {
+                d + 1
+            } else {
+                d
+            }
+        });
+
+        let history = automaton.history(usize::MAX);
+        assert_eq!(history.len(), 3);
+        assert!(automaton.is_stable());
+
+        let expected = map.distance_transform(Neighbourhood::Orthogonal, |&c| c == '#');
+        assert_eq!(automaton.grid(), &expected);
+        assert_eq!(automaton.grid().rows().flatten().sum::<u32>(), 35);
+    }
+}
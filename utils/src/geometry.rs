@@ -0,0 +1,201 @@
+//! Geometry on polygons given as a sequence of `Point` vertices.
+//!
+//! Polygons are closed implicitly, so the last vertex joins back up to the
+//! first. A loop traced cell by cell through a grid is a valid polygon, as is
+//! one made from just its corners. All arithmetic is done in `i64` so huge
+//! polygons don't overflow, even though `Point` itself uses `i32`.
+
+use crate::grid::Point;
+
+/// Which way round the vertices of a polygon run.
+///
+/// Remember `y` increases downward, so these are as seen on screen when a
+/// grid is drawn with row 0 at the top.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+}
+
+/// Where a point lies relative to a polygon.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Containment {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// Iterate over the edges of a polygon as `(from, to)` pairs in `i64`,
+/// including the closing edge from the last vertex back to the first.
+fn edges(polygon: &[Point]) -> impl Iterator<Item = ((i64, i64), (i64, i64))> + '_ {
+    let wide = |p: &Point| (p.x as i64, p.y as i64);
+
+    polygon
+        .iter()
+        .map(wide)
+        .zip(polygon.iter().cycle().skip(1).map(wide))
+}
+
+/// Twice the signed area of the polygon, using the shoelace formula.
+///
+/// The result is positive when the vertices run clockwise on screen, and
+/// negative when they run counter-clockwise.
+pub fn signed_double_area(polygon: &[Point]) -> i64 {
+    edges(polygon)
+        .map(|((x1, y1), (x2, y2))| x1 * y2 - x2 * y1)
+        .sum()
+}
+
+/// Twice the area enclosed by the polygon. Doubling keeps the result exact,
+/// since polygons with diagonal edges can have half-unit areas.
+pub fn double_area(polygon: &[Point]) -> i64 {
+    signed_double_area(polygon).abs()
+}
+
+/// Area enclosed by the polygon, rounded down to a whole number. This is always
+/// exact when every edge is horizontal or vertical.
+pub fn area(polygon: &[Point]) -> i64 {
+    double_area(polygon) / 2
+}
+
+/// Number of unit lattice steps around the boundary, which is also the number
+/// of lattice points lying on it. For a loop of cells traced through a grid
+/// this is just the number of cells in the loop.
+pub fn boundary_length(polygon: &[Point]) -> i64 {
+    if polygon.len() < 2 {
+        return 0;
+    }
+
+    edges(polygon)
+        .map(|((x1, y1), (x2, y2))| gcd((x2 - x1).abs(), (y2 - y1).abs()))
+        .sum()
+}
+
+/// Number of lattice points strictly inside the polygon, using Pick's theorem.
+///
+/// For a loop of cells traced through a grid this is the number of cells the
+/// loop encloses, not counting the loop itself.
+pub fn interior_points(polygon: &[Point]) -> i64 {
+    if double_area(polygon) == 0 {
+        return 0;
+    }
+
+    (double_area(polygon) - boundary_length(polygon) + 2) / 2
+}
+
+/// Which way round the polygon runs, or `None` if it encloses no area.
+pub fn orientation(polygon: &[Point]) -> Option<Orientation> {
+    match signed_double_area(polygon) {
+        0 => None,
+        a if a > 0 => Some(Orientation::Clockwise),
+        _ => Some(Orientation::CounterClockwise),
+    }
+}
+
+/// Find whether `p` is inside, outside or on the boundary of the polygon.
+pub fn locate(polygon: &[Point], p: Point) -> Containment {
+    let (px, py) = (p.x as i64, p.y as i64);
+    let mut inside = false;
+
+    for ((ax, ay), (bx, by)) in edges(polygon) {
+        let cross = (bx - ax) * (py - ay) - (px - ax) * (by - ay);
+
+        let within_x = ax.min(bx) <= px && px <= ax.max(bx);
+        let within_y = ay.min(by) <= py && py <= ay.max(by);
+
+        if cross == 0 && within_x && within_y {
+            return Containment::Boundary;
+        }
+
+        // cast a ray to the right, counting the edges it crosses
+        if (ay > py) != (by > py) && (cross > 0) == (by > ay) {
+            inside = !inside;
+        }
+    }
+
+    if inside {
+        Containment::Inside
+    } else {
+        Containment::Outside
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Direction, Grid};
+    use std::str::FromStr;
+
+    fn points(coords: &[(i32, i32)]) -> Vec<Point> {
+        coords.iter().map(|&p| p.into()).collect()
+    }
+
+    #[test]
+    fn rectangle_from_corners() {
+        let clockwise = points(&[(0, 0), (4, 0), (4, 3), (0, 3)]);
+
+        assert_eq!(signed_double_area(&clockwise), 24);
+        assert_eq!(area(&clockwise), 12);
+        assert_eq!(boundary_length(&clockwise), 14);
+        assert_eq!(interior_points(&clockwise), 6);
+        assert_eq!(orientation(&clockwise), Some(Orientation::Clockwise));
+
+        let anticlockwise: Vec<Point> = clockwise.iter().rev().copied().collect();
+        assert_eq!(signed_double_area(&anticlockwise), -24);
+        assert_eq!(
+            orientation(&anticlockwise),
+            Some(Orientation::CounterClockwise)
+        );
+        assert_eq!(area(&anticlockwise), 12);
+    }
+
+    #[test]
+    fn triangle_with_half_area() {
+        let triangle = points(&[(0, 0), (3, 1), (1, 2)]);
+
+        assert_eq!(double_area(&triangle), 5);
+        assert_eq!(boundary_length(&triangle), 3);
+        assert_eq!(interior_points(&triangle), 2);
+    }
+
+    #[test]
+    fn traced_loop_interior() {
+        let grid = Grid::from_str("S+===\n-   +\n=+=-+").unwrap();
+        let track = grid
+            .trace_path(Point::new(0, 0), |&c| c != ' ', Some(Direction::East))
+            .unwrap();
+
+        assert_eq!(boundary_length(&track.points), 12);
+        assert_eq!(interior_points(&track.points), 3);
+    }
+
+    #[test]
+    fn huge_coordinates_do_not_overflow() {
+        let big = points(&[
+            (-1_000_000_000, -1_000_000_000),
+            (1_000_000_000, -1_000_000_000),
+            (1_000_000_000, 1_000_000_000),
+        ]);
+
+        assert_eq!(area(&big), 2_000_000_000_000_000_000);
+        assert_eq!(boundary_length(&big), 6_000_000_000);
+    }
+
+    #[test]
+    fn point_location() {
+        let shape = points(&[(0, 0), (4, 0), (4, 4), (2, 2), (0, 4)]);
+
+        assert_eq!(locate(&shape, Point::new(1, 1)), Containment::Inside);
+        assert_eq!(locate(&shape, Point::new(2, 3)), Containment::Outside);
+        assert_eq!(locate(&shape, Point::new(3, 3)), Containment::Boundary);
+        assert_eq!(locate(&shape, Point::new(4, 2)), Containment::Boundary);
+        assert_eq!(locate(&shape, Point::new(0, 0)), Containment::Boundary);
+        assert_eq!(locate(&shape, Point::new(5, 0)), Containment::Outside);
+        assert_eq!(locate(&shape, Point::new(-1, 2)), Containment::Outside);
+        assert_eq!(locate(&shape, Point::new(3, 2)), Containment::Inside);
+    }
+}
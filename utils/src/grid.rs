@@ -1,49 +1,87 @@
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::convert::Infallible;
+use std::error::Error;
 use std::fmt;
-use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
 use std::str::FromStr;
 
+mod bits;
+mod compress;
+mod coord;
+mod diff;
+mod direction;
+mod distance;
+mod grid3d;
+mod line;
+mod pattern;
+mod rect;
+mod regions;
+mod render;
+mod sparse;
+mod trace;
+
+pub use bits::BitGrid;
+pub use compress::{Compressor, Weighted};
+pub use coord::Coord;
+pub use diff::{Change, GridDiff};
+pub use direction::{Direction, Direction3D, ParseDirectionError};
+pub use grid3d::{Axis, Grid3D};
+pub use line::{Line, Trajectory};
+pub use pattern::{Match, Transform};
+pub use rect::Rect;
+pub use regions::Region;
+pub use render::{Colour, Layer};
+pub use sparse::SparseGrid;
+pub use trace::{Trace, TraceError};
+
 /// A 2D point with signed integer coordinates.
 ///
 /// The coordinate system assumes `x` is the column (increasing to the right)
 /// and `y` is the row (increasing downward). This matches common 2D grid
 /// indexing where `grid[y][x]` accesses the cell at `(x, y)`.
+///
+/// Most code uses the `i32` form through the `Point` alias, but any `Coord`
+/// type can be used when coordinates might get too big for that.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
-pub struct Point {
-    pub x: i32,
-    pub y: i32,
+pub struct Point2<C: Coord = i32> {
+    pub x: C,
+    pub y: C,
 }
 
-impl Point {
-    /// Create a new `Point`.
-    pub fn new(x: i32, y: i32) -> Self {
+/// A 2D point with `i32` coordinates, which is plenty for anything indexing a `Grid`.
+pub type Point = Point2<i32>;
+
+impl<C: Coord> Point2<C> {
+    /// Create a new `Point2`.
+    pub fn new(x: C, y: C) -> Self {
         Self { x, y }
     }
 
     /// The origin `(0, 0)`.
     pub fn zero() -> Self {
-        Self { x: 0, y: 0 }
+        Self::new(C::ZERO, C::ZERO)
     }
 
     /// Move one step north (up / y - 1).
     pub fn north(&self) -> Self {
-        Self::new(self.x, self.y - 1)
+        Self::new(self.x, self.y - C::ONE)
     }
 
     /// Move one step south (down / y + 1).
     pub fn south(&self) -> Self {
-        Self::new(self.x, self.y + 1)
+        Self::new(self.x, self.y + C::ONE)
     }
 
     /// Move one step east (right / x + 1).
     pub fn east(&self) -> Self {
-        Self::new(self.x + 1, self.y)
+        Self::new(self.x + C::ONE, self.y)
     }
 
     /// Move one step west (left / x - 1).
     pub fn west(&self) -> Self {
-        Self::new(self.x - 1, self.y)
+        Self::new(self.x - C::ONE, self.y)
     }
 
     /// Return the 4 orthogonally adjacent points in NESW order.
@@ -55,107 +93,122 @@ impl Point {
     pub fn neighbours8(&self) -> [Self; 8] {
         [
             self.north(),
-            Self::new(self.x + 1, self.y - 1), // NE
+            self.north().east(),
             self.east(),
-            Self::new(self.x + 1, self.y + 1), // SE
+            self.south().east(),
             self.south(),
-            Self::new(self.x - 1, self.y + 1), // SW
+            self.south().west(),
             self.west(),
-            Self::new(self.x - 1, self.y - 1), // NW
+            self.north().west(),
         ]
     }
 
+    /// Return the points adjacent to this one in the given `Neighbourhood`.
+    pub fn neighbours<'a>(
+        &self,
+        neighbourhood: Neighbourhood<'a>,
+    ) -> impl Iterator<Item = Self> + use<'a, C> {
+        let p = *self;
+
+        neighbourhood
+            .deltas()
+            .iter()
+            .map(move |&(dx, dy)| Self::new(p.x + C::from_i32(dx), p.y + C::from_i32(dy)))
+    }
+
     /// Manhattan distance from another point.
-    pub fn manhattan_distance(&self, other: Point) -> i32 {
-        (self.x - other.x).abs() + (self.y - other.y).abs()
+    ///
+    /// The sum is taken in `u64` so it can't overflow the coordinate type.
+    /// Only `i64` points can be further apart than that, and their distance
+    /// saturates to `u64::MAX`, so all such distances compare equal.
+    pub fn manhattan_distance(&self, other: Self) -> u64 {
+        self.x
+            .abs_diff_u64(other.x)
+            .saturating_add(self.y.abs_diff_u64(other.y))
     }
 
-    /// Move in a `Compass` direction by `n` steps (can be negative).
-    pub fn moved(&self, dir: Compass, n: i32) -> Self {
+    /// Move in a `Direction` by `n` steps (can be negative).
+    pub fn moved(&self, dir: Direction, n: C) -> Self {
         let (dx, dy) = dir.delta();
-        Self::new(self.x + dx * n, self.y + dy * n)
+        Self::new(self.x + C::from_i32(dx) * n, self.y + C::from_i32(dy) * n)
     }
 
-    /// Move in a `Compass` direction by one step.
-    pub fn step(&self, dir: Compass) -> Self {
-        self.moved(dir, 1)
+    /// Move in a `Direction` by one step.
+    pub fn step(&self, dir: Direction) -> Self {
+        self.moved(dir, C::ONE)
     }
 
-    /// Convert to a (x, y) tuple of i32.
-    pub fn to_tuple(&self) -> (i32, i32) {
+    /// Convert to a (x, y) tuple.
+    pub fn to_tuple(&self) -> (C, C) {
         (self.x, self.y)
     }
 
     /// Returns true if this point lies within the bounds of `grid`.
     /// Negative coordinates are out-of-bounds.
     pub fn in_bounds<T>(&self, grid: &Grid<T>) -> bool {
-        if self.x < 0 || self.y < 0 {
-            return false;
+        match (self.x.to_usize(), self.y.to_usize()) {
+            (Some(x), Some(y)) => y < grid.height() && x < grid.width(),
+            _ => false,
         }
-
-        let x = self.x as usize;
-        let y = self.y as usize;
-
-        y < grid.height() && x < grid.width()
     }
 }
 
-impl From<(i32, i32)> for Point {
-    fn from(t: (i32, i32)) -> Self {
+impl<C: Coord> From<(C, C)> for Point2<C> {
+    fn from(t: (C, C)) -> Self {
         Self::new(t.0, t.1)
     }
 }
 
-impl From<Point> for (i32, i32) {
-    fn from(p: Point) -> Self {
+impl<C: Coord> From<Point2<C>> for (C, C) {
+    fn from(p: Point2<C>) -> Self {
         (p.x, p.y)
     }
 }
 
-impl fmt::Display for Point {
+impl<C: Coord> fmt::Display for Point2<C> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "({}, {})", self.x, self.y)
     }
 }
 
 // Basic arithmetic for points (component-wise)
-impl Add for Point {
-    type Output = Point;
+impl<C: Coord> Add for Point2<C> {
+    type Output = Self;
 
-    fn add(self, rhs: Point) -> Point {
-        Point::new(self.x + rhs.x, self.y + rhs.y)
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
     }
 }
 
-impl Sub for Point {
-    type Output = Point;
+impl<C: Coord> Sub for Point2<C> {
+    type Output = Self;
 
-    fn sub(self, rhs: Point) -> Point {
-        Point::new(self.x - rhs.x, self.y - rhs.y)
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
     }
 }
 
-impl AddAssign for Point {
-    fn add_assign(&mut self, rhs: Point) {
+impl<C: Coord> AddAssign for Point2<C> {
+    fn add_assign(&mut self, rhs: Self) {
         self.x += rhs.x;
         self.y += rhs.y;
     }
 }
 
-impl SubAssign for Point {
-    fn sub_assign(&mut self, rhs: Point) {
+impl<C: Coord> SubAssign for Point2<C> {
+    fn sub_assign(&mut self, rhs: Self) {
         self.x -= rhs.x;
         self.y -= rhs.y;
     }
 }
 
-impl PartialOrd for Point {
-    fn partial_cmp(&self, other: &Point) -> Option<Ordering> {
+impl<C: Coord> PartialOrd for Point2<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl Ord for Point {
+impl<C: Coord> Ord for Point2<C> {
     fn cmp(&self, other: &Self) -> Ordering {
         self.x.cmp(&other.x).then(self.y.cmp(&other.y))
     }
@@ -166,42 +219,165 @@ impl Ord for Point {
 /// The y axis doesn't behave like the 2d point because this isn't really used
 /// for indexing into an array or anything
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub struct Point3D {
-    pub x: i32,
-    pub y: i32,
-    pub z: i32,
+pub struct Point3<C: Coord = i32> {
+    pub x: C,
+    pub y: C,
+    pub z: C,
 }
 
-impl Point3D {
-    pub fn new(x: i32, y: i32, z: i32) -> Self {
+/// A 3D point with `i32` coordinates.
+pub type Point3D = Point3<i32>;
+
+impl<C: Coord> Point3<C> {
+    pub fn new(x: C, y: C, z: C) -> Self {
         Self { x, y, z }
     }
 
-    /// Mahnattan distance from another 3d point
-    pub fn manhattan_distance(&self, other: Point3D) -> u32 {
-        (self.x - other.x).unsigned_abs()
-            + (self.y - other.y).unsigned_abs()
-            + (self.z - other.z).unsigned_abs()
+    /// The origin `(0, 0, 0)`.
+    pub fn zero() -> Self {
+        Self::new(C::ZERO, C::ZERO, C::ZERO)
+    }
+
+    /// Manhattan distance from another 3d point, summed in `u64` like
+    /// `Point2::manhattan_distance`. Distances past `u64::MAX` saturate to it,
+    /// so all such distances compare equal.
+    pub fn manhattan_distance(&self, other: Self) -> u64 {
+        self.x
+            .abs_diff_u64(other.x)
+            .saturating_add(self.y.abs_diff_u64(other.y))
+            .saturating_add(self.z.abs_diff_u64(other.z))
     }
 
     /// Return the 6 adjacent points (U, D, L, R, F, B).
     pub fn neighbours6(&self) -> [Self; 6] {
+        let one = C::ONE;
+
         [
-            Self::new(self.x, self.y + 1, self.z), // Up
-            Self::new(self.x, self.y - 1, self.z), // Down
-            Self::new(self.x - 1, self.y, self.z), // Left
-            Self::new(self.x + 1, self.y, self.z), // Right
-            Self::new(self.x, self.y, self.z + 1), // Forward
-            Self::new(self.x, self.y, self.z - 1), // Backwards
+            Self::new(self.x, self.y + one, self.z), // Up
+            Self::new(self.x, self.y - one, self.z), // Down
+            Self::new(self.x - one, self.y, self.z), // Left
+            Self::new(self.x + one, self.y, self.z), // Right
+            Self::new(self.x, self.y, self.z + one), // Forward
+            Self::new(self.x, self.y, self.z - one), // Backwards
         ]
     }
+
+    /// Return all 26 points in the surrounding 3x3x3 cube, ordered by z, then
+    /// y, then x.
+    pub fn neighbours26(&self) -> [Self; 26] {
+        let one = C::ONE;
+        let offsets = [-one, C::ZERO, one];
+        let mut neighbours = [*self; 26];
+        let mut i = 0;
+
+        for dz in offsets {
+            for dy in offsets {
+                for dx in offsets {
+                    if dx != C::ZERO || dy != C::ZERO || dz != C::ZERO {
+                        neighbours[i] = *self + Self::new(dx, dy, dz);
+                        i += 1;
+                    }
+                }
+            }
+        }
+
+        neighbours
+    }
+
+    /// Chebyshev distance from another 3d point, i.e. the most steps needed
+    /// along any one axis when diagonal moves are allowed. Returned as `u64`
+    /// like `manhattan_distance`.
+    pub fn chebyshev_distance(&self, other: Self) -> u64 {
+        self.x
+            .abs_diff_u64(other.x)
+            .max(self.y.abs_diff_u64(other.y))
+            .max(self.z.abs_diff_u64(other.z))
+    }
+
+    /// Rotate by `quarter_turns` about `axis` through the origin, anticlockwise
+    /// when looking back along the axis from its positive end (the right hand
+    /// rule). Negative values rotate the other way.
+    pub fn rotate(&self, axis: Axis, quarter_turns: i32) -> Self {
+        let mut p = *self;
+
+        for _ in 0..quarter_turns.rem_euclid(4) {
+            p = match axis {
+                Axis::X => Self::new(p.x, -p.z, p.y),
+                Axis::Y => Self::new(p.z, p.y, -p.x),
+                Axis::Z => Self::new(-p.y, p.x, p.z),
+            };
+        }
+
+        p
+    }
+
+    /// This point under each of the 24 rotations of a cube about the origin.
+    /// The first is the point unchanged.
+    pub fn orientations(&self) -> [Self; 24] {
+        // point the x axis each of the 6 ways, then spin 4 ways around it
+        let facings = [
+            (Axis::Y, 0),
+            (Axis::Y, 1),
+            (Axis::Y, 2),
+            (Axis::Y, 3),
+            (Axis::Z, 1),
+            (Axis::Z, 3),
+        ];
+        let mut orientations = [*self; 24];
+
+        for (i, (axis, turns)) in facings.into_iter().enumerate() {
+            for spin in 0..4 {
+                orientations[i * 4 + spin] = self.rotate(Axis::X, spin as i32).rotate(axis, turns);
+            }
+        }
+
+        orientations
+    }
+}
+
+impl<C: Coord> From<(C, C, C)> for Point3<C> {
+    fn from(t: (C, C, C)) -> Self {
+        Self::new(t.0, t.1, t.2)
+    }
+}
+
+impl<C: Coord> fmt::Display for Point3<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
 }
 
-impl Add for Point3D {
-    type Output = Point3D;
+/// Parse a point from `"x,y,z"`, allowing spaces around each number.
+impl<C: Coord + FromStr> FromStr for Point3<C> {
+    type Err = ParsePointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(',').map(|part| part.trim().parse::<C>());
+
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(Ok(x)), Some(Ok(y)), Some(Ok(z)), None) => Ok(Self::new(x, y, z)),
+            _ => Err(ParsePointError(s.to_string())),
+        }
+    }
+}
+
+/// The text which couldn't be parsed as a point.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParsePointError(pub String);
+
+impl fmt::Display for ParsePointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid point {:?}", self.0)
+    }
+}
+
+impl Error for ParsePointError {}
+
+impl<C: Coord> Add for Point3<C> {
+    type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
-        Point3D {
+        Self {
             x: self.x + rhs.x,
             y: self.y + rhs.y,
             z: self.z + rhs.z,
@@ -209,7 +385,7 @@ impl Add for Point3D {
     }
 }
 
-impl AddAssign for Point3D {
+impl<C: Coord> AddAssign for Point3<C> {
     fn add_assign(&mut self, rhs: Self) {
         self.x += rhs.x;
         self.y += rhs.y;
@@ -217,11 +393,39 @@ impl AddAssign for Point3D {
     }
 }
 
-impl Mul<i32> for Point3D {
-    type Output = Point3D;
+impl<C: Coord> Sub for Point3<C> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+        }
+    }
+}
+
+impl<C: Coord> SubAssign for Point3<C> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl<C: Coord> Neg for Point3<C> {
+    type Output = Self;
 
-    fn mul(self, rhs: i32) -> Self::Output {
-        Point3D {
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<C: Coord> Mul<C> for Point3<C> {
+    type Output = Self;
+
+    fn mul(self, rhs: C) -> Self::Output {
+        Self {
             x: self.x * rhs,
             y: self.y * rhs,
             z: self.z * rhs,
@@ -229,38 +433,116 @@ impl Mul<i32> for Point3D {
     }
 }
 
-/// Cardinal directions useful for moving a `Point`.
+impl<C: Coord> PartialOrd for Point3<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<C: Coord> Ord for Point3<C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.x
+            .cmp(&other.x)
+            .then(self.y.cmp(&other.y))
+            .then(self.z.cmp(&other.z))
+    }
+}
+
+/// The set of offsets considered adjacent to a cell when looking around a `Point`.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum Compass {
-    North,
-    East,
-    South,
-    West,
+pub enum Neighbourhood<'a> {
+    /// The 4 orthogonally adjacent cells in NESW order.
+    Orthogonal,
+    /// All 8 surrounding cells (N, NE, E, SE, S, SW, W, NW).
+    All,
+    /// A custom set of `(dx, dy)` offsets, e.g. a knight's moves.
+    Custom(&'a [(i32, i32)]),
 }
 
-impl Compass {
-    /// Delta vector for this compass direction as (dx, dy).
-    /// Remember `y` increases downward for grid indexing.
-    pub fn delta(&self) -> (i32, i32) {
+impl<'a> Neighbourhood<'a> {
+    const ORTHOGONAL: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+    const ALL: [(i32, i32); 8] = [
+        (0, -1),
+        (1, -1),
+        (1, 0),
+        (1, 1),
+        (0, 1),
+        (-1, 1),
+        (-1, 0),
+        (-1, -1),
+    ];
+
+    /// The `(dx, dy)` offsets which make up this neighbourhood.
+    pub fn deltas(&self) -> &'a [(i32, i32)] {
         match self {
-            Compass::North => (0, -1),
-            Compass::East => (1, 0),
-            Compass::South => (0, 1),
-            Compass::West => (-1, 0),
+            Neighbourhood::Orthogonal => &Self::ORTHOGONAL,
+            Neighbourhood::All => &Self::ALL,
+            Neighbourhood::Custom(deltas) => deltas,
         }
     }
 }
 
+/// Which axes of a `Grid` wrap around to the opposite edge, e.g. for maps
+/// drawn on the surface of a cylinder or torus.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Wrap {
+    /// Neither axis wraps, so this behaves like normal bounds-checked access.
+    #[default]
+    None,
+    /// Moving off the left or right edge comes back on the opposite side.
+    Horizontal,
+    /// Moving off the top or bottom edge comes back on the opposite side.
+    Vertical,
+    /// Both axes wrap, i.e. the grid is a torus.
+    Both,
+}
+
+impl Wrap {
+    /// Returns true if the x axis wraps.
+    pub fn horizontal(&self) -> bool {
+        matches!(self, Wrap::Horizontal | Wrap::Both)
+    }
+
+    /// Returns true if the y axis wraps.
+    pub fn vertical(&self) -> bool {
+        matches!(self, Wrap::Vertical | Wrap::Both)
+    }
+}
+
 /// Simple rectangular grid wrapper around `Vec<Vec<T>>`.
 ///
 /// The grid is rectangular: every row must have the same number of columns.
+/// Ragged input can be padded out to a rectangle with `from_rows_padded`, or
+/// with `from_rows_ragged` if the real length of each row needs to be kept.
+///
+/// Grids are equal when they have the same size and cells. Recorded row
+/// extents are ignored, so a ragged grid equals its padded copy.
+#[derive(Clone, Debug)]
 pub struct Grid<T> {
     data: Vec<Vec<T>>,
     width: usize,
     height: usize,
+    extents: Option<Vec<usize>>,
 }
 
+impl<T: PartialEq> PartialEq for Grid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width && self.height == other.height && self.data == other.data
+    }
+}
+
+impl<T: Eq> Eq for Grid<T> {}
+
 impl<T> Grid<T> {
+    /// Construct a `width` x `height` grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_rows(vec![vec![value; width]; height])
+    }
+
     /// Construct a grid from rows. Panics if rows have differing lengths.
     pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
         let height = rows.len();
@@ -274,7 +556,36 @@ impl<T> Grid<T> {
             data: rows,
             width,
             height,
+            extents: None,
+        }
+    }
+
+    /// Construct a grid from rows which may have differing lengths, padding
+    /// every short row with `filler` up to the width of the widest row.
+    pub fn from_rows_padded(mut rows: Vec<Vec<T>>, filler: T) -> Self
+    where
+        T: Clone,
+    {
+        let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+
+        for row in rows.iter_mut() {
+            row.resize(width, filler.clone());
         }
+
+        Self::from_rows(rows)
+    }
+
+    /// Like `from_rows_padded`, but also records the real length of each row
+    /// so padding can be told apart from real cells with `in_extent`.
+    pub fn from_rows_ragged(rows: Vec<Vec<T>>, filler: T) -> Self
+    where
+        T: Clone,
+    {
+        let extents = rows.iter().map(|r| r.len()).collect();
+
+        let mut grid = Self::from_rows_padded(rows, filler);
+        grid.extents = Some(extents);
+        grid
     }
 
     /// Height (number of rows)
@@ -287,6 +598,32 @@ impl<T> Grid<T> {
         self.width
     }
 
+    /// The real length of row `y` before any padding was added. This is the
+    /// full width unless the grid was built with `from_rows_ragged`.
+    ///
+    /// Returns `None` if `y` is outside the grid.
+    pub fn row_extent(&self, y: usize) -> Option<usize> {
+        if y >= self.height {
+            return None;
+        }
+
+        match &self.extents {
+            Some(extents) => Some(extents[y]),
+            None => Some(self.width),
+        }
+    }
+
+    /// Returns true if `p` is within the grid and isn't a padding cell added
+    /// to the end of a short row.
+    pub fn in_extent(&self, p: Point) -> bool {
+        if p.x < 0 || p.y < 0 {
+            return false;
+        }
+
+        self.row_extent(p.y as usize)
+            .is_some_and(|extent| (p.x as usize) < extent)
+    }
+
     /// Immutable access by `Point`. Returns `None` if the point is negative or
     /// outside the grid bounds.
     pub fn at(&self, p: Point) -> Option<&T> {
@@ -320,6 +657,121 @@ impl<T> Grid<T> {
         }
     }
 
+    /// Map `p` back into the grid by wrapping the axes selected by `wrap`.
+    /// Coordinates on a wrapping axis are taken modulo the grid size, so
+    /// negative coordinates count back from the opposite edge.
+    ///
+    /// Returns `None` if `p` is still outside the grid on a non-wrapping axis.
+    pub fn wrap_point(&self, p: Point, wrap: Wrap) -> Option<Point> {
+        let mut wrapped = p;
+
+        if wrap.horizontal() && self.width > 0 {
+            wrapped.x = p.x.rem_euclid(self.width as i32);
+        }
+
+        if wrap.vertical() && self.height > 0 {
+            wrapped.y = p.y.rem_euclid(self.height as i32);
+        }
+
+        wrapped.in_bounds(self).then_some(wrapped)
+    }
+
+    /// Immutable access by `Point`, wrapping around the axes selected by `wrap`.
+    pub fn at_wrapped(&self, p: Point, wrap: Wrap) -> Option<&T> {
+        self.wrap_point(p, wrap).and_then(|p| self.at(p))
+    }
+
+    /// Mutable access by `Point`, wrapping around the axes selected by `wrap`.
+    pub fn at_wrapped_mut(&mut self, p: Point, wrap: Wrap) -> Option<&mut T> {
+        self.wrap_point(p, wrap).and_then(|p| self.at_mut(p))
+    }
+
+    /// Iterate over the neighbours of `p` in the given `Neighbourhood`, wrapping
+    /// around the axes selected by `wrap`. The returned points are the wrapped
+    /// positions, so they can be used directly with `at`.
+    pub fn neighbours_wrapped<'a>(
+        &'a self,
+        p: Point,
+        neighbourhood: Neighbourhood<'a>,
+        wrap: Wrap,
+    ) -> impl Iterator<Item = (Point, &'a T)> {
+        p.neighbours(neighbourhood).filter_map(move |n| {
+            let n = self.wrap_point(n, wrap)?;
+            self.at(n).map(|cell| (n, cell))
+        })
+    }
+
+    /// Iterate over the in-bounds orthogonal neighbours of `p` in NESW order,
+    /// along with their cells.
+    pub fn neighbours4(&self, p: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.neighbours(p, Neighbourhood::Orthogonal)
+    }
+
+    /// Iterate over all 8 in-bounds neighbours of `p` along with their cells.
+    pub fn neighbours8(&self, p: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.neighbours(p, Neighbourhood::All)
+    }
+
+    /// Iterate over the neighbours of `p` in the given `Neighbourhood`,
+    /// skipping any which fall outside the grid.
+    pub fn neighbours<'a>(
+        &'a self,
+        p: Point,
+        neighbourhood: Neighbourhood<'a>,
+    ) -> impl Iterator<Item = (Point, &'a T)> {
+        p.neighbours(neighbourhood)
+            .filter_map(|n| self.at(n).map(|cell| (n, cell)))
+    }
+
+    /// Iterate over every cell along with its `Point`, in reading order
+    /// (left to right, then top to bottom).
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.data.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(x, cell)| (Point::new(x as i32, y as i32), cell))
+        })
+    }
+
+    /// Iterate over every `Point` in the grid in reading order.
+    pub fn positions(&self) -> impl Iterator<Item = Point> + use<T> {
+        let width = self.width;
+
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point::new(x as i32, y as i32)))
+    }
+
+    /// Find the first cell equal to `value` in reading order.
+    pub fn find(&self, value: &T) -> Option<Point>
+    where
+        T: PartialEq,
+    {
+        self.iter().find(|(_, cell)| *cell == value).map(|(p, _)| p)
+    }
+
+    /// Find every cell matching `predicate`, in reading order.
+    pub fn find_all<F>(&self, mut predicate: F) -> impl Iterator<Item = Point>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.iter()
+            .filter_map(move |(p, cell)| predicate(cell).then_some(p))
+    }
+
+    /// Build an index from each distinct value to the positions holding it,
+    /// each in reading order, in a single pass over the grid.
+    pub fn index_by_value(&self) -> HashMap<T, Vec<Point>>
+    where
+        T: Clone + Eq + Hash,
+    {
+        let mut index: HashMap<T, Vec<Point>> = HashMap::new();
+
+        for (p, cell) in self.iter() {
+            index.entry(cell.clone()).or_default().push(p);
+        }
+
+        index
+    }
+
     /// Borrowing iterator over rows as slices (`&[T]`). Useful when you want
     /// to iterate rows without allocating or cloning.
     pub fn rows(&self) -> impl Iterator<Item = &[T]> {
@@ -366,18 +818,32 @@ impl FromStr for Grid<char> {
     /// Parse a string where each line is a row and each char is a cell.
     /// Panics if the rows are not all the same length.
     fn from_str(s: &str) -> Result<Self, Infallible> {
-        let rows = s
-            .lines()
-            .map(|l| l.chars().collect::<Vec<char>>())
-            .collect::<Vec<Vec<char>>>();
+        Ok(Self::from_rows(Self::char_rows(s)))
+    }
+}
+
+impl Grid<char> {
+    /// Parse a string where each line is a row, padding short lines with
+    /// `filler`. Useful for hand-drawn maps with trimmed trailing spaces.
+    pub fn parse_padded(s: &str, filler: char) -> Self {
+        Self::from_rows_padded(Self::char_rows(s), filler)
+    }
+
+    /// Parse a string where each line is a row, padding short lines with
+    /// `filler` and recording the real length of each line.
+    pub fn parse_ragged(s: &str, filler: char) -> Self {
+        Self::from_rows_ragged(Self::char_rows(s), filler)
+    }
 
-        Ok(Self::from_rows(rows))
+    fn char_rows(s: &str) -> Vec<Vec<char>> {
+        s.lines().map(|l| l.chars().collect()).collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashSet;
 
     #[test]
     fn point_add_sub_basic() {
@@ -406,14 +872,106 @@ mod tests {
         assert_eq!(n8[6], Point::new(1, 2)); // W
         assert_eq!(n8[7], Point::new(1, 1)); // NW
 
-        assert_eq!(p.step(Compass::North), Point::new(2, 1));
-        assert_eq!(p.moved(Compass::West, 3), Point::new(-1, 2));
+        assert_eq!(p.step(Direction::North), Point::new(2, 1));
+        assert_eq!(p.moved(Direction::West, 3), Point::new(-1, 2));
 
         // Manhattan distance
         assert_eq!(p.manhattan_distance(Point::new(5, 7)), 8); // dx=3 dy=5 -> 8
         assert_eq!(Point::new(-1, -1).manhattan_distance(Point::new(1, 2)), 5);
     }
 
+    #[test]
+    fn generic_coordinates() {
+        let far = Point2::<i64>::new(3_000_000_000, -3_000_000_000);
+        assert_eq!(far.manhattan_distance(Point2::zero()), 6_000_000_000);
+        assert_eq!(
+            far.moved(Direction::South, 2),
+            Point2::new(3_000_000_000, -2_999_999_998)
+        );
+        assert_eq!(far.to_string(), "(3000000000, -3000000000)");
+
+        // spans across several axes can be larger than the unsigned type
+        let left = Point2::<i16>::new(i16::MIN, 0);
+        assert_eq!(left.manhattan_distance(Point2::new(i16::MAX, 0)), 65_535);
+        let corner = Point2::<i16>::new(i16::MIN, i16::MIN);
+        assert_eq!(
+            corner.manhattan_distance(Point2::new(i16::MAX, i16::MAX)),
+            131_070
+        );
+
+        // i64 distances too big for u64 saturate, and so compare equal
+        let corner = Point2::<i64>::new(i64::MIN, i64::MIN);
+        let far = corner.manhattan_distance(Point2::new(i64::MAX, 0));
+        let further = corner.manhattan_distance(Point2::new(i64::MAX, i64::MAX));
+        assert_eq!((far, further), (u64::MAX, u64::MAX));
+
+        let corner = Point3::<i64>::new(i64::MIN, i64::MIN, 0);
+        assert_eq!(
+            corner.manhattan_distance(Point3::new(i64::MAX, i64::MAX, 0)),
+            u64::MAX
+        );
+        assert_eq!(
+            corner.manhattan_distance(Point3::new(i64::MAX, i64::MAX, i64::MAX)),
+            u64::MAX
+        );
+
+        let p = Point3::<isize>::new(1, -2, 3);
+        assert_eq!(p.manhattan_distance(Point3::new(-1, 2, -3)), 12);
+        assert_eq!(p * 2, Point3::new(2, -4, 6));
+        assert!(!Point2::<i64>::new(-1, 0).in_bounds(&Grid::new(2, 2, ())));
+    }
+
+    #[test]
+    fn point3d_algebra() {
+        let a = Point3D::new(1, -2, 3);
+        let b: Point3D = "4, 5,-6".parse().unwrap();
+
+        assert_eq!(b, Point3D::new(4, 5, -6));
+        assert_eq!(a - b, Point3D::new(-3, -7, 9));
+        assert_eq!(-a, Point3D::new(-1, 2, -3));
+        assert_eq!(a.to_string(), "(1, -2, 3)");
+        assert_eq!(a.chebyshev_distance(b), 9);
+        assert_eq!(
+            Point3::<i16>::new(i16::MIN, 0, 0).chebyshev_distance(Point3::new(i16::MAX, 1, 0)),
+            65_535u64
+        );
+        assert!(a < b && Point3D::new(1, -2, 4) > a);
+        assert!("1,2".parse::<Point3D>().is_err());
+        assert!("1,2,3,4".parse::<Point3D>().is_err());
+        assert_eq!(
+            "1,x,3".parse::<Point3D>(),
+            Err(ParsePointError("1,x,3".into()))
+        );
+
+        let mut c = b;
+        c -= a;
+        assert_eq!(c, b - a);
+    }
+
+    #[test]
+    fn point3d_neighbours_and_rotations() {
+        let p = Point3D::new(5, 5, 5);
+        let n26 = p.neighbours26();
+        assert_eq!(n26[0], Point3D::new(4, 4, 4));
+        assert_eq!(n26[25], Point3D::new(6, 6, 6));
+        assert!(n26.iter().all(|&n| n != p && n.chebyshev_distance(p) == 1));
+        assert_eq!(n26.iter().collect::<HashSet<_>>().len(), 26);
+
+        let q = Point3D::new(1, 2, 3);
+        assert_eq!(q.rotate(Axis::Z, 1), Point3D::new(-2, 1, 3));
+        assert_eq!(q.rotate(Axis::X, -1), Point3D::new(1, 3, -2));
+        assert_eq!(q.rotate(Axis::Y, 2), Point3D::new(-1, 2, -3));
+
+        let orientations = q.orientations();
+        assert_eq!(orientations[0], q);
+        assert_eq!(orientations.iter().collect::<HashSet<_>>().len(), 24);
+        assert!(
+            orientations
+                .iter()
+                .all(|o| o.manhattan_distance(Point3D::zero()) == 6)
+        );
+    }
+
     #[test]
     fn grid_at_behaviour() {
         let rows = vec![vec![1, 2, 3], vec![4, 5, 6]];
@@ -449,6 +1007,139 @@ mod tests {
         let _ = Grid::from_str("ab\nc");
     }
 
+    #[test]
+    fn parse_padded_fills_short_rows() {
+        let grid = Grid::parse_padded("S+=\n-\n=+=-+", ' ');
+        assert_eq!(grid.width(), 5);
+        assert_eq!(grid.height(), 3);
+        assert_eq!(grid.at(Point::new(4, 0)), Some(&' '));
+        assert_eq!(grid.at(Point::new(0, 1)), Some(&'-'));
+        assert_eq!(grid.at(Point::new(1, 1)), Some(&' '));
+        assert_eq!(grid.row_extent(1), Some(5));
+    }
+
+    #[test]
+    fn parse_ragged_records_extents() {
+        let grid = Grid::parse_ragged("ab\nc\n\ndef", '.');
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.at(Point::new(2, 0)), Some(&'.'));
+
+        assert_eq!(grid.row_extent(0), Some(2));
+        assert_eq!(grid.row_extent(1), Some(1));
+        assert_eq!(grid.row_extent(2), Some(0));
+        assert_eq!(grid.row_extent(3), Some(3));
+        assert_eq!(grid.row_extent(4), None);
+
+        assert!(grid.in_extent(Point::new(1, 0)));
+        assert!(!grid.in_extent(Point::new(2, 0)));
+        assert!(!grid.in_extent(Point::new(0, 2)));
+        assert!(!grid.in_extent(Point::new(-1, 3)));
+    }
+
+    #[test]
+    fn grid_search_helpers() {
+        let grid = Grid::from_str("S.#\n#.E\n..#").unwrap();
+
+        assert_eq!(grid.find(&'S'), Some(Point::new(0, 0)));
+        assert_eq!(grid.find(&'E'), Some(Point::new(2, 1)));
+        assert_eq!(grid.find(&'X'), None);
+
+        let walls: Vec<Point> = grid.find_all(|&c| c == '#').collect();
+        assert_eq!(
+            walls,
+            vec![Point::new(2, 0), Point::new(0, 1), Point::new(2, 2)]
+        );
+
+        let positions: Vec<Point> = grid.positions().collect();
+        assert_eq!(positions.len(), 9);
+        assert_eq!(positions[0], Point::new(0, 0));
+        assert_eq!(positions[3], Point::new(0, 1));
+        assert_eq!(positions[8], Point::new(2, 2));
+
+        let index = grid.index_by_value();
+        assert_eq!(index[&'S'], vec![Point::new(0, 0)]);
+        assert_eq!(index[&'#'], walls);
+        assert_eq!(index[&'.'].len(), 4);
+    }
+
+    #[test]
+    fn grid_neighbours_skip_out_of_bounds() {
+        let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+
+        let corner: Vec<(Point, &i32)> = grid.neighbours4(Point::new(0, 0)).collect();
+        assert_eq!(corner, vec![(Point::new(1, 0), &2), (Point::new(0, 1), &4)]);
+
+        let middle: Vec<&i32> = grid.neighbours8(Point::new(1, 0)).map(|(_, c)| c).collect();
+        assert_eq!(middle, vec![&3, &6, &5, &4, &1]);
+
+        const KNIGHT: [(i32, i32); 2] = [(2, 1), (-2, 1)];
+        let custom: Vec<Point> = grid
+            .neighbours(Point::new(0, 0), Neighbourhood::Custom(&KNIGHT))
+            .map(|(p, _)| p)
+            .collect();
+        assert_eq!(custom, vec![Point::new(2, 1)]);
+
+        assert_eq!(grid.neighbours4(Point::new(5, 5)).count(), 0);
+    }
+
+    #[test]
+    fn grid_wrapped_access() {
+        let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+
+        assert_eq!(
+            grid.at_wrapped(Point::new(3, 0), Wrap::Horizontal),
+            Some(&1)
+        );
+        assert_eq!(
+            grid.at_wrapped(Point::new(-1, 1), Wrap::Horizontal),
+            Some(&6)
+        );
+        assert_eq!(
+            grid.at_wrapped(Point::new(-4, 0), Wrap::Horizontal),
+            Some(&3)
+        );
+        assert_eq!(grid.at_wrapped(Point::new(0, 2), Wrap::Horizontal), None);
+
+        assert_eq!(grid.at_wrapped(Point::new(0, -1), Wrap::Vertical), Some(&4));
+        assert_eq!(grid.at_wrapped(Point::new(3, 0), Wrap::Vertical), None);
+
+        assert_eq!(grid.at_wrapped(Point::new(-1, -1), Wrap::Both), Some(&6));
+        assert_eq!(grid.at_wrapped(Point::new(7, 5), Wrap::Both), Some(&5));
+        assert_eq!(grid.at_wrapped(Point::new(3, 0), Wrap::None), None);
+
+        let mut grid = grid;
+        *grid.at_wrapped_mut(Point::new(-3, -2), Wrap::Both).unwrap() = 9;
+        assert_eq!(grid.at(Point::new(0, 0)), Some(&9));
+    }
+
+    #[test]
+    fn grid_neighbours_wrapped() {
+        let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+
+        let torus: Vec<(Point, &i32)> = grid
+            .neighbours_wrapped(Point::new(0, 0), Neighbourhood::Orthogonal, Wrap::Both)
+            .collect();
+        assert_eq!(
+            torus,
+            vec![
+                (Point::new(0, 1), &4),
+                (Point::new(1, 0), &2),
+                (Point::new(0, 1), &4),
+                (Point::new(2, 0), &3),
+            ]
+        );
+
+        let cylinder: Vec<&i32> = grid
+            .neighbours_wrapped(
+                Point::new(2, 1),
+                Neighbourhood::Orthogonal,
+                Wrap::Horizontal,
+            )
+            .map(|(_, c)| c)
+            .collect();
+        assert_eq!(cylinder, vec![&3, &4, &5]);
+    }
+
     #[test]
     fn point_in_bounds() {
         let rows = vec![vec![0; 3], vec![0; 3]]; // 2 rows x 3 cols
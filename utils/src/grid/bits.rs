@@ -0,0 +1,390 @@
+use super::{Grid, Point};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Sub, SubAssign};
+
+/// A rectangular grid of booleans packed 64 cells to a `u64` word.
+///
+/// Each row starts on a fresh word, so whole rows can be combined and shifted
+/// a word at a time. This makes set operations and spreading a region out by
+/// one cell in every direction much faster than working point by point.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    width: usize,
+    height: usize,
+    row_words: usize,
+}
+
+impl BitGrid {
+    /// Create a `width` x `height` grid with every cell unset.
+    pub fn new(width: usize, height: usize) -> Self {
+        let row_words = width.div_ceil(64);
+
+        Self {
+            words: vec![0; row_words * height],
+            width,
+            height,
+            row_words,
+        }
+    }
+
+    /// Create a grid the same size as `grid`, setting every cell which
+    /// matches `predicate`.
+    pub fn from_grid<T, F>(grid: &Grid<T>, mut predicate: F) -> Self
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut bits = Self::new(grid.width(), grid.height());
+
+        for p in grid.find_all(|cell| predicate(cell)) {
+            bits.insert(p);
+        }
+
+        bits
+    }
+
+    /// Width (number of columns)
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height (number of rows)
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns true if `p` is set. Out of bounds points are never set.
+    pub fn contains(&self, p: Point) -> bool {
+        self.locate(p)
+            .is_some_and(|(word, bit)| self.words[word] & bit != 0)
+    }
+
+    /// Set the cell at `p`, returning true if it wasn't already set.
+    ///
+    /// Panics if `p` is out of bounds.
+    pub fn insert(&mut self, p: Point) -> bool {
+        let (word, bit) = self.locate(p).expect("Point is outside the BitGrid");
+        let added = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        added
+    }
+
+    /// Unset the cell at `p`, returning true if it was set.
+    pub fn remove(&mut self, p: Point) -> bool {
+        let Some((word, bit)) = self.locate(p) else {
+            return false;
+        };
+
+        let removed = self.words[word] & bit != 0;
+        self.words[word] &= !bit;
+        removed
+    }
+
+    /// Number of cells which are set
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns true if no cells are set.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// Iterate over the points which are set, in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = Point> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, &word)| {
+            let y = i / self.row_words;
+            let base = (i % self.row_words) * 64;
+
+            BitsSet(word).map(move |bit| Point::new((base + bit) as i32, y as i32))
+        })
+    }
+
+    /// Cells set in either grid. Panics if the grids are different sizes.
+    pub fn union(&self, other: &BitGrid) -> BitGrid {
+        self.combine(other, |a, b| a | b)
+    }
+
+    /// Cells set in both grids. Panics if the grids are different sizes.
+    pub fn intersection(&self, other: &BitGrid) -> BitGrid {
+        self.combine(other, |a, b| a & b)
+    }
+
+    /// Cells set in this grid but not in `other`. Panics if the grids are
+    /// different sizes.
+    pub fn difference(&self, other: &BitGrid) -> BitGrid {
+        self.combine(other, |a, b| a & !b)
+    }
+
+    /// Grow the set cells by one step in each orthogonal direction.
+    pub fn dilate4(&self) -> BitGrid {
+        let mut out = self.clone();
+        self.spread_horizontally(&mut out);
+        self.spread_vertically(&mut out);
+        out
+    }
+
+    /// Grow the set cells by one step in all 8 directions.
+    pub fn dilate8(&self) -> BitGrid {
+        let mut horizontal = self.clone();
+        self.spread_horizontally(&mut horizontal);
+
+        let mut out = horizontal.clone();
+        horizontal.spread_vertically(&mut out);
+        out
+    }
+
+    /// OR each row of `self`, shifted one cell left and one cell right, into `out`.
+    fn spread_horizontally(&self, out: &mut BitGrid) {
+        // a zero-width grid has no words to chunk rows into
+        if self.row_words == 0 {
+            return;
+        }
+
+        for (src, dst) in self
+            .words
+            .chunks(self.row_words)
+            .zip(out.words.chunks_mut(self.row_words))
+        {
+            // moving east shifts towards the high bits, carrying into the next word
+            let mut carry = 0;
+            for (s, d) in src.iter().zip(dst.iter_mut()) {
+                *d |= (s << 1) | carry;
+                carry = s >> 63;
+            }
+
+            let mut carry = 0;
+            for (s, d) in src.iter().zip(dst.iter_mut()).rev() {
+                *d |= (s >> 1) | carry;
+                carry = s << 63;
+            }
+        }
+
+        out.clear_padding();
+    }
+
+    /// OR each row of `self` into the rows above and below it in `out`.
+    fn spread_vertically(&self, out: &mut BitGrid) {
+        let n = self.row_words;
+
+        for y in 0..self.height {
+            let row = y * n;
+
+            if y > 0 {
+                for i in 0..n {
+                    out.words[row - n + i] |= self.words[row + i];
+                }
+            }
+
+            if y + 1 < self.height {
+                for i in 0..n {
+                    out.words[row + n + i] |= self.words[row + i];
+                }
+            }
+        }
+    }
+
+    /// Unset any bits past the end of each row, which shifting east can set.
+    fn clear_padding(&mut self) {
+        let used = self.width % 64;
+
+        if used == 0 {
+            return;
+        }
+
+        let mask = (1u64 << used) - 1;
+
+        for row in self.words.chunks_mut(self.row_words) {
+            if let Some(last) = row.last_mut() {
+                *last &= mask;
+            }
+        }
+    }
+
+    fn combine(&self, other: &BitGrid, f: impl Fn(u64, u64) -> u64) -> BitGrid {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "BitGrids must be the same size"
+        );
+
+        BitGrid {
+            words: self
+                .words
+                .iter()
+                .zip(other.words.iter())
+                .map(|(&a, &b)| f(a, b))
+                .collect(),
+            ..*self
+        }
+    }
+
+    /// The word index and bit mask for `p`, or `None` if out of bounds.
+    fn locate(&self, p: Point) -> Option<(usize, u64)> {
+        if p.x < 0 || p.y < 0 || p.x as usize >= self.width || p.y as usize >= self.height {
+            return None;
+        }
+
+        let (x, y) = (p.x as usize, p.y as usize);
+        Some((y * self.row_words + x / 64, 1 << (x % 64)))
+    }
+}
+
+/// Iterator over the indices of the set bits in a word, lowest first.
+struct BitsSet(u64);
+
+impl Iterator for BitsSet {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let bit = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+        Some(bit)
+    }
+}
+
+impl BitOr for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitor(self, rhs: &BitGrid) -> BitGrid {
+        self.union(rhs)
+    }
+}
+
+impl BitAnd for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitand(self, rhs: &BitGrid) -> BitGrid {
+        self.intersection(rhs)
+    }
+}
+
+impl Sub for &BitGrid {
+    type Output = BitGrid;
+
+    fn sub(self, rhs: &BitGrid) -> BitGrid {
+        self.difference(rhs)
+    }
+}
+
+impl BitOrAssign<&BitGrid> for BitGrid {
+    fn bitor_assign(&mut self, rhs: &BitGrid) {
+        *self = self.union(rhs);
+    }
+}
+
+impl BitAndAssign<&BitGrid> for BitGrid {
+    fn bitand_assign(&mut self, rhs: &BitGrid) {
+        *self = self.intersection(rhs);
+    }
+}
+
+impl SubAssign<&BitGrid> for BitGrid {
+    fn sub_assign(&mut self, rhs: &BitGrid) {
+        *self = self.difference(rhs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn bits(s: &str) -> BitGrid {
+        BitGrid::from_grid(&Grid::from_str(s).unwrap(), |&c| c == '#')
+    }
+
+    fn render(b: &BitGrid) -> String {
+        let grid = Grid::new(b.width(), b.height(), '.');
+        grid.render_with(|p, &c| if b.contains(p) { '#' } else { c })
+    }
+
+    #[test]
+    fn insert_remove_and_count() {
+        let mut grid = BitGrid::new(130, 2);
+        assert!(grid.is_empty());
+
+        assert!(grid.insert(Point::new(0, 0)));
+        assert!(grid.insert(Point::new(64, 0)));
+        assert!(grid.insert(Point::new(129, 1)));
+        assert!(!grid.insert(Point::new(129, 1)));
+        assert_eq!(grid.count_ones(), 3);
+
+        assert!(grid.contains(Point::new(64, 0)));
+        assert!(!grid.contains(Point::new(63, 0)));
+        assert!(!grid.contains(Point::new(130, 1)));
+        assert!(!grid.contains(Point::new(-1, 0)));
+
+        assert!(grid.remove(Point::new(64, 0)));
+        assert!(!grid.remove(Point::new(64, 0)));
+
+        let set: Vec<Point> = grid.iter().collect();
+        assert_eq!(set, vec![Point::new(0, 0), Point::new(129, 1)]);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = bits("##.\n...");
+        let b = bits(".##\n..#");
+
+        assert_eq!(render(&(&a | &b)), "###\n..#");
+        assert_eq!(render(&(&a & &b)), ".#.\n...");
+        assert_eq!(render(&(&a - &b)), "#..\n...");
+
+        let mut c = a.clone();
+        c |= &b;
+        c -= &a;
+        assert_eq!(render(&c), "..#\n..#");
+    }
+
+    #[test]
+    fn dilation() {
+        let centre = bits(".....\n.....\n..#..\n.....\n.....");
+
+        assert_eq!(
+            render(&centre.dilate4()),
+            ".....\n..#..\n.###.\n..#..\n....."
+        );
+        assert_eq!(
+            render(&centre.dilate8()),
+            ".....\n.###.\n.###.\n.###.\n....."
+        );
+
+        let corner = bits("#..\n...");
+        assert_eq!(render(&corner.dilate8()), "##.\n##.");
+    }
+
+    #[test]
+    fn dilation_crosses_word_boundaries() {
+        let mut grid = BitGrid::new(65, 1);
+        grid.insert(Point::new(63, 0));
+        let grown = grid.dilate4();
+        assert_eq!(grown.count_ones(), 3);
+        assert!(grown.contains(Point::new(64, 0)));
+
+        // growing off the right edge mustn't leave bits in the row padding
+        let mut edge = BitGrid::new(65, 1);
+        edge.insert(Point::new(64, 0));
+        let grown = edge.dilate4();
+        assert_eq!(grown.count_ones(), 2);
+        assert_eq!(
+            grown.iter().collect::<Vec<_>>(),
+            vec![Point::new(63, 0), Point::new(64, 0)]
+        );
+
+        let mut low = BitGrid::new(70, 1);
+        low.insert(Point::new(64, 0));
+        assert!(low.dilate4().contains(Point::new(63, 0)));
+    }
+
+    #[test]
+    fn dilation_of_empty_grids() {
+        for grid in [BitGrid::new(0, 3), BitGrid::new(3, 0), BitGrid::new(0, 0)] {
+            assert!(grid.dilate4().is_empty());
+            assert!(grid.dilate8().is_empty());
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use super::{Coord, Grid, Point, Point2};
+
+/// Maps points spread over a huge coordinate space onto a small dense `Grid`.
+///
+/// Every distinct `x` (and `y`) value from the original points gets its own
+/// column (row), and each gap between neighbouring values is squashed into a
+/// single column (row) standing for the whole gap. Cells stay adjacent exactly
+/// when the real ranges they cover are adjacent, so flood fills and region
+/// labelling give the same answers on the compressed grid, and the real area
+/// of anything can be found by adding up the area of its cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Compressor<C: Coord = i32> {
+    xs: Vec<(C, u64)>,
+    ys: Vec<(C, u64)>,
+}
+
+/// A cell of a compressed grid, along with the real area it stands for.
+///
+/// Areas too big for `u64`, which `i64` coordinates can reach, saturate to
+/// `u64::MAX` like the distances in `metric`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Weighted<T> {
+    pub value: T,
+    pub area: u64,
+}
+
+impl<C: Coord> Compressor<C> {
+    /// Create a compressor covering the bounding box of `points`.
+    ///
+    /// Nothing outside the bounding box can be compressed, so include points
+    /// one beyond each edge if the grid needs a border to flood fill around.
+    pub fn new(points: impl IntoIterator<Item = Point2<C>>) -> Self {
+        let (xs, ys) = points.into_iter().map(|p| (p.x, p.y)).unzip();
+
+        Self {
+            xs: spans(xs),
+            ys: spans(ys),
+        }
+    }
+
+    /// Number of columns in the compressed grid.
+    pub fn width(&self) -> usize {
+        self.xs.len()
+    }
+
+    /// Number of rows in the compressed grid.
+    pub fn height(&self) -> usize {
+        self.ys.len()
+    }
+
+    /// The compressed cell containing the real point `p`, or `None` if it's
+    /// outside the bounding box.
+    pub fn compress(&self, p: Point2<C>) -> Option<Point> {
+        let x = locate(&self.xs, p.x)?;
+        let y = locate(&self.ys, p.y)?;
+
+        Some(Point::new(x as i32, y as i32))
+    }
+
+    /// The first real point covered by the compressed cell `p`, and how many
+    /// real columns and rows the cell covers from there.
+    pub fn decompress(&self, p: Point) -> Option<(Point2<C>, u64, u64)> {
+        let &(x, width) = self.xs.get(usize::try_from(p.x).ok()?)?;
+        let &(y, height) = self.ys.get(usize::try_from(p.y).ok()?)?;
+
+        Some((Point2::new(x, y), width, height))
+    }
+
+    /// The real area covered by the compressed cell `p`, saturating at
+    /// `u64::MAX`.
+    pub fn area(&self, p: Point) -> Option<u64> {
+        self.decompress(p)
+            .map(|(_, width, height)| width.saturating_mul(height))
+    }
+
+    /// A compressed grid with every cell set to `value`, and knowing the real
+    /// area it covers.
+    pub fn grid<T: Clone>(&self, value: T) -> Grid<Weighted<T>> {
+        let rows = self
+            .ys
+            .iter()
+            .map(|&(_, height)| {
+                self.xs
+                    .iter()
+                    .map(|&(_, width)| Weighted {
+                        value: value.clone(),
+                        area: width.saturating_mul(height),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Grid::from_rows(rows)
+    }
+}
+
+/// Sort and dedupe the values along one axis, then turn them into
+/// `(start, length)` spans with the gaps between them filled in.
+fn spans<C: Coord>(mut values: Vec<C>) -> Vec<(C, u64)> {
+    values.sort_unstable();
+    values.dedup();
+
+    let mut spans = Vec::with_capacity(values.len() * 2);
+
+    for (i, &v) in values.iter().enumerate() {
+        spans.push((v, 1));
+
+        if let Some(&next) = values.get(i + 1) {
+            let gap = next.abs_diff_u64(v) - 1;
+
+            if gap > 0 {
+                spans.push((v + C::ONE, gap));
+            }
+        }
+    }
+
+    spans
+}
+
+/// Index of the span containing `v`, if any.
+fn locate<C: Coord>(spans: &[(C, u64)], v: C) -> Option<usize> {
+    let i = spans
+        .partition_point(|&(start, _)| start <= v)
+        .checked_sub(1)?;
+    let (start, length) = spans[i];
+
+    (v.abs_diff_u64(start) < length).then_some(i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Line;
+
+    #[test]
+    fn compress_and_decompress() {
+        let compressor = Compressor::new([
+            Point2::<i64>::new(-5, 0),
+            Point2::new(1_000_000, 10),
+            Point2::new(-4, 3_000_000_000),
+        ]);
+
+        // x: -5, -4, gap, 1_000_000 / y: 0, gap, 10, gap, 3_000_000_000
+        assert_eq!((compressor.width(), compressor.height()), (4, 5));
+
+        assert_eq!(
+            compressor.compress(Point2::new(-5, 0)),
+            Some(Point::new(0, 0))
+        );
+        assert_eq!(
+            compressor.compress(Point2::new(500, 7)),
+            Some(Point::new(2, 1))
+        );
+        assert_eq!(
+            compressor.compress(Point2::new(1_000_000, 3_000_000_000)),
+            Some(Point::new(3, 4))
+        );
+        assert_eq!(compressor.compress(Point2::new(1_000_001, 0)), None);
+        assert_eq!(compressor.compress(Point2::new(0, -1)), None);
+
+        assert_eq!(
+            compressor.decompress(Point::new(2, 3)),
+            Some((Point2::new(-3, 11), 1_000_003, 2_999_999_989))
+        );
+        assert_eq!(compressor.decompress(Point::new(4, 0)), None);
+
+        let total: u64 = compressor.grid(()).iter().map(|(_, cell)| cell.area).sum();
+        assert_eq!(total, 1_000_006 * 3_000_000_001);
+    }
+
+    #[test]
+    fn huge_areas_saturate() {
+        let compressor = Compressor::new([
+            Point2::<i64>::new(i64::MIN, i64::MIN),
+            Point2::new(i64::MAX, i64::MAX),
+        ]);
+
+        // the gap between the two points on each axis is 2^64 - 2 long
+        let gap = Point::new(1, 1);
+        assert_eq!(
+            compressor.decompress(gap),
+            Some((
+                Point2::new(i64::MIN + 1, i64::MIN + 1),
+                u64::MAX - 1,
+                u64::MAX - 1
+            ))
+        );
+        assert_eq!(compressor.area(gap), Some(u64::MAX));
+        assert_eq!(compressor.area(Point::new(0, 1)), Some(u64::MAX - 1));
+        assert_eq!(compressor.area(Point::new(2, 2)), Some(1));
+
+        let grid = compressor.grid(());
+        assert_eq!(grid.at(gap).unwrap().area, u64::MAX);
+    }
+
+    #[test]
+    fn flood_fill_compressed_area() {
+        // a huge rectangle outline with a border around it to fill from
+        let corners = [
+            Point::new(0, 0),
+            Point::new(100_000, 0),
+            Point::new(100_000, 30_000),
+            Point::new(0, 30_000),
+        ];
+        let border = [Point::new(-1, -1), Point::new(100_001, 30_001)];
+        let compressor = Compressor::new(corners.into_iter().chain(border));
+
+        let mut grid = compressor.grid('.');
+        let compressed = corners.map(|p| compressor.compress(p).unwrap());
+
+        for (i, &from) in compressed.iter().enumerate() {
+            for p in Line::new(from, compressed[(i + 1) % 4]) {
+                grid.at_mut(p).unwrap().value = '#';
+            }
+        }
+
+        let outside = grid
+            .flood_fill(Point::new(0, 0), |cell| cell.value == '.')
+            .unwrap();
+        let outside_area: u64 = outside
+            .cells
+            .iter()
+            .map(|&p| grid.at(p).unwrap().area)
+            .sum();
+
+        assert_eq!(outside_area, 100_003 * 30_003 - 100_001 * 30_001);
+    }
+}
@@ -0,0 +1,75 @@
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A signed integer type which can be used for the coordinates of a `Point2`
+/// or `Point3`.
+///
+/// This is implemented for `i16`, `i32`, `i64` and `isize`, so puzzles with
+/// huge coordinates can use the same helpers as ordinary grids.
+pub trait Coord:
+    Copy
+    + Debug
+    + Display
+    + Default
+    + Eq
+    + Ord
+    + Hash
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+{
+    /// The unsigned type of the same width, used for distances.
+    type Unsigned: Copy + Debug + Display + Default + Eq + Ord + Hash + Add<Output = Self::Unsigned>;
+
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// The absolute difference between two coordinates, which can't overflow.
+    fn abs_diff(self, other: Self) -> Self::Unsigned;
+
+    /// `abs_diff` widened to `u64`, so distances can be compared across types.
+    fn abs_diff_u64(self, other: Self) -> u64;
+
+    /// Convert a small offset, such as a neighbourhood delta.
+    ///
+    /// Panics if `n` doesn't fit in this type.
+    fn from_i32(n: i32) -> Self;
+
+    /// Convert to an index, or `None` if negative or too big.
+    fn to_usize(self) -> Option<usize>;
+}
+
+macro_rules! impl_coord {
+    ($($t:ty => $u:ty),*) => {
+        $(
+            impl Coord for $t {
+                type Unsigned = $u;
+
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn abs_diff(self, other: Self) -> $u {
+                    <$t>::abs_diff(self, other)
+                }
+
+                fn abs_diff_u64(self, other: Self) -> u64 {
+                    <$t>::abs_diff(self, other) as u64
+                }
+
+                fn from_i32(n: i32) -> Self {
+                    <$t>::try_from(n).expect("Offset out of range for coordinate type")
+                }
+
+                fn to_usize(self) -> Option<usize> {
+                    usize::try_from(self).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_coord!(i16 => u16, i32 => u32, i64 => u64, isize => usize);
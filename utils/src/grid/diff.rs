@@ -0,0 +1,228 @@
+use super::{Colour, Grid, Layer, Point};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A cell which differs between two grids, as found by `Grid::diff`.
+///
+/// When the grids are different sizes, cells which only exist in one of them
+/// have `None` on the other side.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change<'a, T> {
+    pub at: Point,
+    pub old: Option<&'a T>,
+    pub new: Option<&'a T>,
+}
+
+/// Every cell which changed between two grids, in reading order.
+#[derive(Clone, Debug)]
+pub struct GridDiff<'a, T> {
+    old: &'a Grid<T>,
+    new: &'a Grid<T>,
+    changes: Vec<Change<'a, T>>,
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Compare this grid with `other`, finding every cell that isn't the same
+    /// in both. `self` is taken as the old state and `other` as the new one.
+    pub fn diff<'a>(&'a self, other: &'a Grid<T>) -> GridDiff<'a, T> {
+        let width = self.width.max(other.width);
+        let height = self.height.max(other.height);
+
+        let changes = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Point::new(x as i32, y as i32)))
+            .filter_map(|at| {
+                let (old, new) = (self.at(at), other.at(at));
+                (old != new).then_some(Change { at, old, new })
+            })
+            .collect();
+
+        GridDiff {
+            old: self,
+            new: other,
+            changes,
+        }
+    }
+}
+
+impl<'a, T> GridDiff<'a, T> {
+    /// The changed cells in reading order.
+    pub fn changes(&self) -> &[Change<'a, T>] {
+        &self.changes
+    }
+
+    /// Number of changed cells.
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Returns true if the grids were identical.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The points of every changed cell in reading order.
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        self.changes.iter().map(|change| change.at)
+    }
+
+    /// How many cells went from each old value to each new value.
+    pub fn transitions(&self) -> HashMap<(Option<&'a T>, Option<&'a T>), usize>
+    where
+        T: Eq + Hash,
+    {
+        let mut counts = HashMap::new();
+
+        for change in &self.changes {
+            *counts.entry((change.old, change.new)).or_insert(0) += 1;
+        }
+
+        counts
+    }
+
+    /// Render the old and new grids next to each other, using `f` to pick the
+    /// char for each cell. Rows containing a change are marked with `*`.
+    ///
+    /// Rows are separated by `\n` with no trailing newline.
+    pub fn side_by_side<F>(&self, mut f: F) -> String
+    where
+        F: FnMut(Point, &T) -> char,
+    {
+        let height = self.old.height.max(self.new.height);
+        let mut changed_rows = vec![false; height];
+
+        for change in &self.changes {
+            changed_rows[change.at.y as usize] = true;
+        }
+
+        let mut render_row = |grid: &Grid<T>, y: usize| -> String {
+            (0..grid.width)
+                .map(|x| {
+                    let p = Point::new(x as i32, y as i32);
+                    grid.at(p).map_or(' ', |cell| f(p, cell))
+                })
+                .collect()
+        };
+
+        changed_rows
+            .iter()
+            .enumerate()
+            .map(|(y, &changed)| {
+                let marker = if changed { '*' } else { ' ' };
+                let old = render_row(self.old, y);
+                let new = render_row(self.new, y);
+
+                format!("{marker} {old} | {new}")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Render the new grid with every changed cell highlighted in red using
+    /// ANSI escape codes, like `Grid::render_ansi`.
+    pub fn highlighted<F>(&self, f: F) -> String
+    where
+        F: FnMut(Point, &T) -> char,
+    {
+        self.new
+            .render_ansi(f, &[Layer::new(self.points(), Colour::Red)])
+    }
+}
+
+/// Assert that two grids are equal, panicking with a side by side diff of the
+/// two if they aren't.
+///
+/// Cells are rendered with `Grid<char>` values as they are, or any other
+/// grid can be compared by also passing a renderer like `Grid::render_with`
+/// takes.
+#[macro_export]
+macro_rules! assert_grid_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_grid_eq!($left, $right, |_, &c: &char| c)
+    };
+    ($left:expr, $right:expr, $render:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let diff = left.diff(right);
+
+                if !diff.is_empty() {
+                    panic!(
+                        "grids differ in {} cells (left | right):\n{}",
+                        diff.len(),
+                        diff.side_by_side($render)
+                    );
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn diff_finds_changes() {
+        let old = Grid::from_str("#..\n.#.\n..#").unwrap();
+        let new = Grid::from_str("#.#\n.#.\n.##").unwrap();
+        let diff = old.diff(&new);
+
+        assert_eq!(diff.len(), 2);
+        assert_eq!(
+            diff.changes()[0],
+            Change {
+                at: Point::new(2, 0),
+                old: Some(&'.'),
+                new: Some(&'#'),
+            }
+        );
+        assert_eq!(
+            diff.points().collect::<Vec<_>>(),
+            vec![Point::new(2, 0), Point::new(1, 2)]
+        );
+        assert_eq!(
+            diff.transitions(),
+            HashMap::from([((Some(&'.'), Some(&'#')), 2)])
+        );
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn diff_different_sizes() {
+        let old = Grid::from_rows(vec![vec![1, 2]]);
+        let new = Grid::from_rows(vec![vec![1], vec![3]]);
+        let diff = old.diff(&new);
+
+        let transitions = diff.transitions();
+        assert_eq!(transitions[&(Some(&2), None)], 1);
+        assert_eq!(transitions[&(None, Some(&3))], 1);
+
+        let rendered = diff.side_by_side(|_, &v| char::from_digit(v, 10).unwrap());
+        assert_eq!(rendered, "* 12 | 1\n*    | 3");
+    }
+
+    #[test]
+    fn rendering() {
+        let old = Grid::from_str("ab\ncd").unwrap();
+        let new = Grid::from_str("ab\nxd").unwrap();
+        let diff = old.diff(&new);
+
+        assert_eq!(diff.side_by_side(|_, &c| c), "  ab | ab\n* cd | xd");
+        assert_eq!(diff.highlighted(|_, &c| c), "ab\n\x1b[31mx\x1b[0md");
+    }
+
+    #[test]
+    fn assert_grid_eq_passes() {
+        let grid = Grid::from_str("ab\ncd").unwrap();
+        assert_grid_eq!(grid, grid.clone());
+        assert_grid_eq!(Grid::new(2, 1, 5u8), Grid::new(2, 1, 5u8), |_, _| '5');
+    }
+
+    #[test]
+    #[should_panic(expected = "grids differ in 1 cells (left | right):\n  ab | ab\n* cd | cx")]
+    fn assert_grid_eq_fails_with_diff() {
+        let left = Grid::from_str("ab\ncd").unwrap();
+        let right = Grid::from_str("ab\ncx").unwrap();
+        assert_grid_eq!(left, right);
+    }
+}
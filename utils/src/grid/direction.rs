@@ -0,0 +1,311 @@
+use super::{Axis, Point3D};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// One of the 8 directions to an adjacent cell, in clockwise order from north.
+///
+/// North is up the screen (`y - 1`). Directions can be parsed
+/// from compass letters (`N`, `NE`, ...), arrows (`^`, `v`, `<`, `>`) or
+/// `U`/`D`/`L`/`R`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    /// All 8 directions in clockwise order starting from north.
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// The 4 orthogonal directions in NESW order.
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// Iterate over all 8 directions in clockwise order starting from north.
+    pub fn iter() -> impl Iterator<Item = Direction> {
+        Self::ALL.into_iter()
+    }
+
+    /// Delta vector for this direction as (dx, dy).
+    pub fn delta(&self) -> (i32, i32) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (0, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (-1, -1),
+        }
+    }
+
+    /// True for the 4 diagonal directions.
+    pub fn is_diagonal(&self) -> bool {
+        (*self as usize) % 2 == 1
+    }
+
+    /// Rotate clockwise by `eighths` of a full turn (45 degrees each).
+    /// Negative values rotate anticlockwise.
+    pub fn rotate(&self, eighths: i32) -> Self {
+        Self::ALL[(*self as i32 + eighths).rem_euclid(8) as usize]
+    }
+
+    /// Turn 45 degrees clockwise.
+    pub fn turn_right_45(&self) -> Self {
+        self.rotate(1)
+    }
+
+    /// Turn 45 degrees anticlockwise.
+    pub fn turn_left_45(&self) -> Self {
+        self.rotate(-1)
+    }
+
+    /// Turn 90 degrees clockwise.
+    pub fn turn_right(&self) -> Self {
+        self.rotate(2)
+    }
+
+    /// Turn 90 degrees anticlockwise.
+    pub fn turn_left(&self) -> Self {
+        self.rotate(-2)
+    }
+
+    /// The direction pointing the other way.
+    pub fn opposite(&self) -> Self {
+        self.rotate(4)
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = ParseDirectionError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'N' | 'U' | '^' => Ok(Direction::North),
+            'E' | 'R' | '>' => Ok(Direction::East),
+            'S' | 'D' | 'v' => Ok(Direction::South),
+            'W' | 'L' | '<' => Ok(Direction::West),
+            _ => Err(ParseDirectionError(c.to_string())),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ParseDirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "NE" => Ok(Direction::NorthEast),
+            "SE" => Ok(Direction::SouthEast),
+            "SW" => Ok(Direction::SouthWest),
+            "NW" => Ok(Direction::NorthWest),
+            _ => {
+                let mut chars = s.chars();
+
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Direction::try_from(c),
+                    _ => Err(ParseDirectionError(s.to_string())),
+                }
+            }
+        }
+    }
+}
+
+/// One of the 6 axis aligned directions in 3D.
+///
+/// Unlike `Direction`, `Up` is `y + 1` since 3D points aren't used for
+/// indexing rows.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction3D {
+    Left,
+    Right,
+    Up,
+    Down,
+    Forwards,
+    Backwards,
+}
+
+impl Direction3D {
+    /// All 6 directions.
+    pub const ALL: [Direction3D; 6] = [
+        Direction3D::Up,
+        Direction3D::Down,
+        Direction3D::Left,
+        Direction3D::Right,
+        Direction3D::Forwards,
+        Direction3D::Backwards,
+    ];
+
+    /// Iterate over all 6 directions.
+    pub fn iter() -> impl Iterator<Item = Direction3D> {
+        Self::ALL.into_iter()
+    }
+
+    /// Unit vector for this direction.
+    pub fn delta(&self) -> Point3D {
+        match self {
+            Direction3D::Left => Point3D::new(-1, 0, 0),
+            Direction3D::Right => Point3D::new(1, 0, 0),
+            Direction3D::Up => Point3D::new(0, 1, 0),
+            Direction3D::Down => Point3D::new(0, -1, 0),
+            Direction3D::Forwards => Point3D::new(0, 0, 1),
+            Direction3D::Backwards => Point3D::new(0, 0, -1),
+        }
+    }
+
+    /// The direction with the given unit vector, if there is one.
+    pub fn from_delta(delta: Point3D) -> Option<Self> {
+        Self::iter().find(|d| d.delta() == delta)
+    }
+
+    /// The axis this direction lies along.
+    pub fn axis(&self) -> Axis {
+        match self {
+            Direction3D::Left | Direction3D::Right => Axis::X,
+            Direction3D::Up | Direction3D::Down => Axis::Y,
+            Direction3D::Forwards | Direction3D::Backwards => Axis::Z,
+        }
+    }
+
+    /// The direction pointing the other way.
+    pub fn opposite(&self) -> Self {
+        match self {
+            Direction3D::Left => Direction3D::Right,
+            Direction3D::Right => Direction3D::Left,
+            Direction3D::Up => Direction3D::Down,
+            Direction3D::Down => Direction3D::Up,
+            Direction3D::Forwards => Direction3D::Backwards,
+            Direction3D::Backwards => Direction3D::Forwards,
+        }
+    }
+
+    /// Rotate by `quarter_turns` around `axis`, in the same sense as
+    /// `Point3D::rotate`.
+    pub fn rotate(&self, axis: Axis, quarter_turns: i32) -> Self {
+        let d = self.delta().rotate(axis, quarter_turns);
+
+        Self::from_delta(d).expect("Rotating a unit vector gives a unit vector")
+    }
+}
+
+impl TryFrom<char> for Direction3D {
+    type Error = ParseDirectionError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'L' => Ok(Direction3D::Left),
+            'R' => Ok(Direction3D::Right),
+            'U' => Ok(Direction3D::Up),
+            'D' => Ok(Direction3D::Down),
+            'F' => Ok(Direction3D::Forwards),
+            'B' => Ok(Direction3D::Backwards),
+            _ => Err(ParseDirectionError(c.to_string())),
+        }
+    }
+}
+
+impl FromStr for Direction3D {
+    type Err = ParseDirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Direction3D::try_from(c),
+            _ => Err(ParseDirectionError(s.to_string())),
+        }
+    }
+}
+
+/// The text which couldn't be parsed as a `Direction` or `Direction3D`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseDirectionError(pub String);
+
+impl fmt::Display for ParseDirectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown direction {:?}", self.0)
+    }
+}
+
+impl Error for ParseDirectionError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turning() {
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Direction::North.turn_left_45(), Direction::NorthWest);
+        assert_eq!(Direction::SouthWest.turn_right_45(), Direction::West);
+        assert_eq!(Direction::NorthEast.opposite(), Direction::SouthWest);
+        assert_eq!(Direction::East.rotate(-10), Direction::North);
+        assert!(Direction::SouthEast.is_diagonal());
+        assert!(!Direction::South.is_diagonal());
+
+        for d in Direction::iter() {
+            let (dx, dy) = d.delta();
+            assert_eq!(d.opposite().delta(), (-dx, -dy));
+            assert_eq!(d.turn_right().turn_left(), d);
+        }
+    }
+
+    #[test]
+    fn parsing() {
+        for (s, d) in [
+            ("U", Direction::North),
+            ("^", Direction::North),
+            ("v", Direction::South),
+            ("L", Direction::West),
+            (">", Direction::East),
+            ("S", Direction::South),
+            ("NW", Direction::NorthWest),
+        ] {
+            assert_eq!(s.parse(), Ok(d));
+        }
+
+        assert_eq!(
+            "X".parse::<Direction>(),
+            Err(ParseDirectionError("X".into()))
+        );
+        assert_eq!("F".parse(), Ok(Direction3D::Forwards));
+        assert!("UD".parse::<Direction3D>().is_err());
+    }
+
+    #[test]
+    fn rotating_3d() {
+        assert_eq!(Direction3D::Right.rotate(Axis::Z, 1), Direction3D::Up);
+        assert_eq!(Direction3D::Up.rotate(Axis::X, 1), Direction3D::Forwards);
+        assert_eq!(Direction3D::Forwards.rotate(Axis::Y, 1), Direction3D::Right);
+        assert_eq!(Direction3D::Up.rotate(Axis::Y, 3), Direction3D::Up);
+        assert_eq!(Direction3D::Left.rotate(Axis::Z, -1), Direction3D::Up);
+
+        for d in Direction3D::iter() {
+            assert_eq!(d.rotate(Axis::X, 2).rotate(Axis::X, 2), d);
+            assert_eq!(d.opposite().delta() * -1, d.delta());
+            assert_eq!(d.rotate(d.axis(), 1), d);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use super::{Grid, Neighbourhood};
+use std::collections::VecDeque;
+
+impl<T> Grid<T> {
+    /// Compute, for every cell, the number of steps through the `neighbourhood`
+    /// to the nearest cell where `inside` returns false.
+    ///
+    /// With `Neighbourhood::Orthogonal` this is the Manhattan distance, and with
+    /// `Neighbourhood::All` it is the Chebyshev distance. Cells outside the grid
+    /// count as outside, so an inside cell on the edge is at distance 1 and
+    /// cells which fail `inside` are at distance 0.
+    ///
+    /// This is a multi-source BFS from every outside cell at once, so it runs in
+    /// time linear in the area of the grid. Custom neighbourhoods should be
+    /// symmetric, i.e. contain `(-dx, -dy)` for every `(dx, dy)`.
+    pub fn distance_transform<F>(
+        &self,
+        neighbourhood: Neighbourhood<'_>,
+        mut inside: F,
+    ) -> Grid<u32>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut distances = Grid::new(self.width, self.height, u32::MAX);
+        let mut edges = vec![];
+        let mut queue = VecDeque::new();
+
+        for (p, cell) in self.iter() {
+            if !inside(cell) {
+                *distances.at_mut(p).unwrap() = 0;
+                queue.push_back(p);
+            } else if p.neighbours(neighbourhood).any(|n| !n.in_bounds(self)) {
+                edges.push(p);
+            }
+        }
+
+        // cells next to the edge of the grid are one step from the outside, and
+        // must be queued after every zero to keep the queue in distance order
+        for p in edges {
+            *distances.at_mut(p).unwrap() = 1;
+            queue.push_back(p);
+        }
+
+        while let Some(current) = queue.pop_front() {
+            let next_distance = distances.at(current).unwrap() + 1;
+
+            for n in current.neighbours(neighbourhood) {
+                if let Some(d) = distances.at_mut(n)
+                    && next_distance < *d
+                {
+                    *d = next_distance;
+                    queue.push_back(n);
+                }
+            }
+        }
+
+        distances
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const MAP: &str = "\
+.......
+.#####.
+.#####.
+.#####.
+.......";
+
+    fn distances(map: &str, neighbourhood: Neighbourhood<'_>) -> Vec<Vec<u32>> {
+        let grid = Grid::from_str(map).unwrap();
+
+        grid.distance_transform(neighbourhood, |&c| c == '#')
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn manhattan_distances() {
+        assert_eq!(
+            distances(MAP, Neighbourhood::Orthogonal),
+            vec![
+                vec![0, 0, 0, 0, 0, 0, 0],
+                vec![0, 1, 1, 1, 1, 1, 0],
+                vec![0, 1, 2, 2, 2, 1, 0],
+                vec![0, 1, 1, 1, 1, 1, 0],
+                vec![0, 0, 0, 0, 0, 0, 0],
+            ]
+        );
+    }
+
+    #[test]
+    fn grid_edge_counts_as_outside() {
+        assert_eq!(
+            distances("####\n####\n####", Neighbourhood::Orthogonal),
+            vec![vec![1, 1, 1, 1], vec![1, 2, 2, 1], vec![1, 1, 1, 1]]
+        );
+    }
+
+    #[test]
+    fn chebyshev_distances() {
+        let map = "\
+#####
+#####
+##.##
+#####
+#####";
+
+        assert_eq!(
+            distances(map, Neighbourhood::All),
+            vec![
+                vec![1, 1, 1, 1, 1],
+                vec![1, 1, 1, 1, 1],
+                vec![1, 1, 0, 1, 1],
+                vec![1, 1, 1, 1, 1],
+                vec![1, 1, 1, 1, 1],
+            ]
+        );
+
+        assert_eq!(
+            distances(map, Neighbourhood::Orthogonal),
+            vec![
+                vec![1, 1, 1, 1, 1],
+                vec![1, 2, 1, 2, 1],
+                vec![1, 1, 0, 1, 1],
+                vec![1, 2, 1, 2, 1],
+                vec![1, 1, 1, 1, 1],
+            ]
+        );
+    }
+}
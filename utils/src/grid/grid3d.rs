@@ -0,0 +1,268 @@
+use super::{Grid, Point3D};
+
+/// One of the three axes of a `Grid3D`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+/// Dense 3D grid of voxels addressed by `Point3D`.
+///
+/// The grid covers a box from `min()` to `max()` inclusive, so coordinates
+/// may be negative. Cells are stored in a single `Vec` for fast lookups.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid3D<T> {
+    data: Vec<T>,
+    min: Point3D,
+    width: usize,
+    height: usize,
+    depth: usize,
+}
+
+impl<T> Grid3D<T> {
+    /// Create a grid from `(0, 0, 0)` with the given size along x, y and z,
+    /// filling every cell with `value`.
+    pub fn new(width: usize, height: usize, depth: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            data: vec![value; width * height * depth],
+            min: Point3D::new(0, 0, 0),
+            width,
+            height,
+            depth,
+        }
+    }
+
+    /// Create a grid covering the box from `min` to `max` inclusive, filling
+    /// every cell with `value`. Panics if `max` is below `min` on any axis.
+    pub fn with_bounds(min: Point3D, max: Point3D, value: T) -> Self
+    where
+        T: Clone,
+    {
+        assert!(
+            min.x <= max.x && min.y <= max.y && min.z <= max.z,
+            "Grid3D bounds {min:?} to {max:?} are inverted"
+        );
+
+        let mut grid = Self::new(
+            (max.x - min.x) as usize + 1,
+            (max.y - min.y) as usize + 1,
+            (max.z - min.z) as usize + 1,
+            value,
+        );
+        grid.min = min;
+        grid
+    }
+
+    /// Size along the x axis
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Size along the y axis
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Size along the z axis
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The smallest corner of the grid.
+    pub fn min(&self) -> Point3D {
+        self.min
+    }
+
+    /// The largest corner of the grid (inclusive).
+    pub fn max(&self) -> Point3D {
+        self.min
+            + Point3D::new(
+                self.width as i32 - 1,
+                self.height as i32 - 1,
+                self.depth as i32 - 1,
+            )
+    }
+
+    /// Returns true if `p` lies within the grid.
+    pub fn contains(&self, p: Point3D) -> bool {
+        self.index(p).is_some()
+    }
+
+    /// Immutable access by `Point3D`. Returns `None` if out of bounds.
+    pub fn at(&self, p: Point3D) -> Option<&T> {
+        self.index(p).map(|i| &self.data[i])
+    }
+
+    /// Mutable access by `Point3D`. Returns `None` if out of bounds.
+    pub fn at_mut(&mut self, p: Point3D) -> Option<&mut T> {
+        self.index(p).map(|i| &mut self.data[i])
+    }
+
+    /// Iterate over every cell along with its `Point3D`, ordered by z, then y, then x.
+    pub fn iter(&self) -> impl Iterator<Item = (Point3D, &T)> {
+        self.data.iter().enumerate().map(|(i, cell)| {
+            let x = i % self.width;
+            let y = (i / self.width) % self.height;
+            let z = i / (self.width * self.height);
+
+            (self.min + Point3D::new(x as i32, y as i32, z as i32), cell)
+        })
+    }
+
+    /// Iterate over the in-bounds neighbours of `p` among its 6 face-adjacent
+    /// points, along with their cells.
+    pub fn neighbours6(&self, p: Point3D) -> impl Iterator<Item = (Point3D, &T)> {
+        p.neighbours6()
+            .into_iter()
+            .filter_map(|n| self.at(n).map(|cell| (n, cell)))
+    }
+
+    /// Take a 2D cross-section through the grid at `position` along `axis`.
+    ///
+    /// The remaining two axes form the columns and rows of the result, in
+    /// ascending order: slicing along `Z` gives x columns and y rows, along
+    /// `Y` gives x columns and z rows, and along `X` gives z columns and y rows.
+    ///
+    /// Returns `None` if `position` is outside the grid.
+    pub fn slice(&self, axis: Axis, position: i32) -> Option<Grid<T>>
+    where
+        T: Clone,
+    {
+        let max = self.max();
+
+        let (range, (cols, rows)) = match axis {
+            Axis::X => (self.min.x..=max.x, (self.depth, self.height)),
+            Axis::Y => (self.min.y..=max.y, (self.width, self.depth)),
+            Axis::Z => (self.min.z..=max.z, (self.width, self.height)),
+        };
+
+        if !range.contains(&position) {
+            return None;
+        }
+
+        let rows = (0..rows as i32)
+            .map(|row| {
+                (0..cols as i32)
+                    .map(|col| {
+                        let offset = match axis {
+                            Axis::X => Point3D::new(0, row, col),
+                            Axis::Y => Point3D::new(col, 0, row),
+                            Axis::Z => Point3D::new(col, row, 0),
+                        };
+
+                        let mut p = self.min + offset;
+                        match axis {
+                            Axis::X => p.x = position,
+                            Axis::Y => p.y = position,
+                            Axis::Z => p.z = position,
+                        }
+
+                        self.at(p).unwrap().clone()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Some(Grid::from_rows(rows))
+    }
+
+    fn index(&self, p: Point3D) -> Option<usize> {
+        let x = p.x - self.min.x;
+        let y = p.y - self.min.y;
+        let z = p.z - self.min.z;
+
+        if x < 0 || y < 0 || z < 0 {
+            return None;
+        }
+
+        let (x, y, z) = (x as usize, y as usize, z as usize);
+
+        if x < self.width && y < self.height && z < self.depth {
+            Some((z * self.height + y) * self.width + x)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered() -> Grid3D<i32> {
+        // value encodes the coordinates as xyz digits, offset from the origin
+        let mut grid = Grid3D::with_bounds(Point3D::new(-1, -1, -1), Point3D::new(1, 0, 1), 0);
+
+        for z in -1..=1 {
+            for y in -1..=0 {
+                for x in -1..=1 {
+                    *grid.at_mut(Point3D::new(x, y, z)).unwrap() =
+                        (x + 1) * 100 + (y + 1) * 10 + (z + 1);
+                }
+            }
+        }
+
+        grid
+    }
+
+    #[test]
+    fn bounds_and_access() {
+        let grid = numbered();
+
+        assert_eq!((grid.width(), grid.height(), grid.depth()), (3, 2, 3));
+        assert_eq!(grid.min(), Point3D::new(-1, -1, -1));
+        assert_eq!(grid.max(), Point3D::new(1, 0, 1));
+
+        assert_eq!(grid.at(Point3D::new(1, -1, 0)), Some(&201));
+        assert_eq!(grid.at(Point3D::new(1, 1, 0)), None);
+        assert_eq!(grid.at(Point3D::new(-2, 0, 0)), None);
+        assert!(grid.contains(Point3D::new(0, 0, 0)));
+
+        assert_eq!(grid.iter().count(), 18);
+        assert!(grid.iter().all(|(p, &v)| grid.at(p) == Some(&v)));
+    }
+
+    #[test]
+    fn in_bounds_neighbours() {
+        let grid = numbered();
+
+        let corner: Vec<i32> = grid
+            .neighbours6(Point3D::new(-1, -1, -1))
+            .map(|(_, &v)| v)
+            .collect();
+        assert_eq!(corner, vec![10, 100, 1]);
+
+        assert_eq!(grid.neighbours6(Point3D::new(0, 0, 0)).count(), 5);
+    }
+
+    #[test]
+    fn slices_along_each_axis() {
+        let grid = numbered();
+
+        let z = grid.slice(Axis::Z, 1).unwrap();
+        assert_eq!(
+            z,
+            Grid::from_rows(vec![vec![2, 102, 202], vec![12, 112, 212]])
+        );
+
+        let y = grid.slice(Axis::Y, 0).unwrap();
+        assert_eq!(
+            y,
+            Grid::from_rows(vec![
+                vec![10, 110, 210],
+                vec![11, 111, 211],
+                vec![12, 112, 212]
+            ])
+        );
+
+        let x = grid.slice(Axis::X, -1).unwrap();
+        assert_eq!(x, Grid::from_rows(vec![vec![0, 1, 2], vec![10, 11, 12]]));
+
+        assert_eq!(grid.slice(Axis::X, 2), None);
+    }
+}
@@ -0,0 +1,218 @@
+use super::{Grid, Point};
+use std::iter::FusedIterator;
+
+/// Iterator over the points on a straight line between two points, as drawn by
+/// Bresenham's algorithm. Both end points are included.
+#[derive(Clone, Debug)]
+pub struct Line {
+    current: Point,
+    end: Point,
+    dx: i32,
+    dy: i32,
+    step_x: i32,
+    step_y: i32,
+    error: i32,
+    done: bool,
+}
+
+impl Line {
+    /// Create a line from `from` to `to` inclusive.
+    pub fn new(from: Point, to: Point) -> Self {
+        let dx = (to.x - from.x).abs();
+        let dy = -(to.y - from.y).abs();
+
+        Self {
+            current: from,
+            end: to,
+            dx,
+            dy,
+            step_x: (to.x - from.x).signum(),
+            step_y: (to.y - from.y).signum(),
+            error: dx + dy,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for Line {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> {
+        if self.done {
+            return None;
+        }
+
+        let p = self.current;
+
+        if p == self.end {
+            self.done = true;
+            return Some(p);
+        }
+
+        let doubled = 2 * self.error;
+
+        if doubled >= self.dy {
+            self.error += self.dy;
+            self.current.x += self.step_x;
+        }
+
+        if doubled <= self.dx {
+            self.error += self.dx;
+            self.current.y += self.step_y;
+        }
+
+        Some(p)
+    }
+}
+
+impl FusedIterator for Line {}
+
+/// The flight path of a projectile which rises diagonally for `rise` steps,
+/// flies level for `flat` steps, then falls diagonally forever.
+///
+/// Every step moves one column to the right. Remember `y` increases downward,
+/// so rising decreases `y`. The start point itself is not yielded.
+#[derive(Clone, Debug)]
+pub struct Trajectory {
+    current: Point,
+    rise: u32,
+    flat: u32,
+    step: u32,
+}
+
+impl Trajectory {
+    /// Create a trajectory from `start` with the given rising and level phases.
+    pub fn new(start: Point, rise: u32, flat: u32) -> Self {
+        Self {
+            current: start,
+            rise,
+            flat,
+            step: 0,
+        }
+    }
+
+    /// A catapult shot with the given power, which rises and then flies level
+    /// for `power` steps each before falling.
+    pub fn catapult(start: Point, power: u32) -> Self {
+        Self::new(start, power, power)
+    }
+}
+
+impl Iterator for Trajectory {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> {
+        let dy = if self.step < self.rise {
+            -1
+        } else if self.step < self.rise + self.flat {
+            0
+        } else {
+            1
+        };
+
+        self.step = self.step.saturating_add(1);
+        self.current += Point::new(1, dy);
+
+        Some(self.current)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Walk from `start` in steps of `(dx, dy)` until leaving the grid, yielding
+    /// each cell passed through. The start point itself is not included.
+    ///
+    /// Panics if the step is `(0, 0)`, which would never leave the grid.
+    pub fn ray(&self, start: Point, (dx, dy): (i32, i32)) -> impl Iterator<Item = (Point, &T)> {
+        assert!(dx != 0 || dy != 0, "A ray must move in some direction");
+
+        let step = Point::new(dx, dy);
+
+        std::iter::successors(Some(start + step), move |&p| Some(p + step))
+            .map_while(|p| self.at(p).map(|cell| (p, cell)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bresenham_lines() {
+        let horizontal: Vec<Point> = Line::new(Point::new(0, 0), Point::new(3, 0)).collect();
+        assert_eq!(
+            horizontal,
+            vec![
+                Point::new(0, 0),
+                Point::new(1, 0),
+                Point::new(2, 0),
+                Point::new(3, 0)
+            ]
+        );
+
+        let shallow: Vec<Point> = Line::new(Point::new(0, 0), Point::new(5, 2)).collect();
+        assert_eq!(
+            shallow,
+            vec![
+                Point::new(0, 0),
+                Point::new(1, 0),
+                Point::new(2, 1),
+                Point::new(3, 1),
+                Point::new(4, 2),
+                Point::new(5, 2)
+            ]
+        );
+
+        let backwards: Vec<Point> = Line::new(Point::new(5, 2), Point::new(0, 0)).collect();
+        assert_eq!(backwards.len(), 6);
+        assert_eq!(backwards.first(), Some(&Point::new(5, 2)));
+        assert_eq!(backwards.last(), Some(&Point::new(0, 0)));
+
+        let single: Vec<Point> = Line::new(Point::new(-1, 5), Point::new(-1, 5)).collect();
+        assert_eq!(single, vec![Point::new(-1, 5)]);
+
+        let steep = Line::new(Point::new(0, 0), Point::new(-2, -7));
+        assert!(
+            steep
+                .clone()
+                .zip(steep.skip(1))
+                .all(|(a, b)| { (a.x - b.x).abs() <= 1 && (a.y - b.y).abs() == 1 })
+        );
+    }
+
+    #[test]
+    fn rays_stop_at_the_edge() {
+        let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]);
+
+        let right: Vec<&i32> = grid.ray(Point::new(0, 1), (1, 0)).map(|(_, c)| c).collect();
+        assert_eq!(right, vec![&5, &6]);
+
+        let diagonal: Vec<Point> = grid
+            .ray(Point::new(2, 2), (-1, -1))
+            .map(|(p, _)| p)
+            .collect();
+        assert_eq!(diagonal, vec![Point::new(1, 1), Point::new(0, 0)]);
+
+        assert_eq!(grid.ray(Point::new(0, 0), (0, -1)).count(), 0);
+        assert_eq!(grid.ray(Point::new(-1, 0), (1, 0)).count(), 3);
+    }
+
+    #[test]
+    fn catapult_trajectory() {
+        let path: Vec<Point> = Trajectory::catapult(Point::new(0, 0), 2).take(7).collect();
+        assert_eq!(
+            path,
+            vec![
+                Point::new(1, -1),
+                Point::new(2, -2),
+                Point::new(3, -2),
+                Point::new(4, -2),
+                Point::new(5, -1),
+                Point::new(6, 0),
+                Point::new(7, 1),
+            ]
+        );
+
+        let falling: Vec<Point> = Trajectory::new(Point::new(0, 0), 0, 0).take(2).collect();
+        assert_eq!(falling, vec![Point::new(1, 1), Point::new(2, 2)]);
+    }
+}
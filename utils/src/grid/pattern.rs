@@ -0,0 +1,295 @@
+use super::{Direction, Grid, Point, Wrap};
+
+/// One of the 8 symmetries of a square, used to rotate and reflect a grid.
+///
+/// Rotations are clockwise as seen on screen, with row 0 at the top.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Transform {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirror left to right.
+    FlipHorizontal,
+    /// Mirror top to bottom.
+    FlipVertical,
+    /// Swap rows and columns, mirroring along the leading diagonal.
+    Transpose,
+    /// Mirror along the other diagonal.
+    AntiTranspose,
+}
+
+impl Transform {
+    /// All 8 transforms, starting with the identity.
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::Transpose,
+        Transform::AntiTranspose,
+    ];
+
+    /// Just the 4 rotations, starting with the identity.
+    pub const ROTATIONS: [Transform; 4] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+    ];
+
+    /// Returns true if this transform swaps the width and height of a grid.
+    pub fn swaps_axes(&self) -> bool {
+        matches!(
+            self,
+            Transform::Rotate90
+                | Transform::Rotate270
+                | Transform::Transpose
+                | Transform::AntiTranspose
+        )
+    }
+
+    /// Where the cell at `p` in a `width` x `height` grid ends up after the
+    /// transform.
+    pub fn apply(&self, p: Point, width: usize, height: usize) -> Point {
+        let (w, h) = (width as i32 - 1, height as i32 - 1);
+
+        match self {
+            Transform::Identity => p,
+            Transform::Rotate90 => Point::new(h - p.y, p.x),
+            Transform::Rotate180 => Point::new(w - p.x, h - p.y),
+            Transform::Rotate270 => Point::new(p.y, w - p.x),
+            Transform::FlipHorizontal => Point::new(w - p.x, p.y),
+            Transform::FlipVertical => Point::new(p.x, h - p.y),
+            Transform::Transpose => Point::new(p.y, p.x),
+            Transform::AntiTranspose => Point::new(h - p.y, w - p.x),
+        }
+    }
+}
+
+/// Where a pattern was found in a grid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Match<O> {
+    /// The first cell of a word, or the top left corner of a template.
+    pub origin: Point,
+    /// The `Direction` a word reads in, or the `Transform` applied to a
+    /// template.
+    pub orientation: O,
+    /// Every cell covered by the pattern, in reading order of the pattern.
+    pub cells: Vec<Point>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// A copy of the grid rotated or reflected by `transform`.
+    pub fn transformed(&self, transform: Transform) -> Grid<T> {
+        let (width, height) = match transform.swaps_axes() {
+            true => (self.height, self.width),
+            false => (self.width, self.height),
+        };
+
+        let mut cells = vec![None; width * height];
+
+        for (p, cell) in self.iter() {
+            let q = transform.apply(p, self.width, self.height);
+            cells[q.y as usize * width + q.x as usize] = Some(cell.clone());
+        }
+
+        let rows = cells
+            .chunks(width.max(1))
+            .map(|row| row.iter().map(|cell| cell.clone().unwrap()).collect())
+            .collect();
+
+        Grid::from_rows(rows)
+    }
+}
+
+impl Grid<char> {
+    /// Find every place `word` can be read in a straight line in one of the
+    /// given `directions`, following `wrap` off the edges of the grid.
+    ///
+    /// A palindrome is found once for each direction it reads in.
+    pub fn find_word(
+        &self,
+        word: &str,
+        directions: &[Direction],
+        wrap: Wrap,
+    ) -> Vec<Match<Direction>> {
+        let word = word.chars().collect::<Vec<_>>();
+        let mut matches = vec![];
+
+        if word.is_empty() {
+            return matches;
+        }
+
+        for origin in self.positions() {
+            if self.at(origin) != Some(&word[0]) {
+                continue;
+            }
+
+            for &direction in directions {
+                let cells = (0..word.len())
+                    .map_while(|i| {
+                        let p = self.wrap_point(origin.moved(direction, i as i32), wrap)?;
+                        (self.at(p) == Some(&word[i])).then_some(p)
+                    })
+                    .collect::<Vec<_>>();
+
+                if cells.len() == word.len() {
+                    matches.push(Match {
+                        origin,
+                        orientation: direction,
+                        cells,
+                    });
+                }
+            }
+        }
+
+        matches
+    }
+
+    /// Find every place the 2D `template` appears after applying one of the
+    /// `transforms`. Template cells holding `wildcard` match anything.
+    ///
+    /// Transforms which leave the template looking the same as an earlier one
+    /// are skipped, so a symmetric template is only found once per place.
+    pub fn find_template(
+        &self,
+        template: &Grid<char>,
+        wildcard: char,
+        transforms: &[Transform],
+    ) -> Vec<Match<Transform>> {
+        let mut variants: Vec<(Transform, Grid<char>)> = vec![];
+
+        for &transform in transforms {
+            let variant = template.transformed(transform);
+
+            if variants.iter().all(|(_, v)| *v != variant) {
+                variants.push((transform, variant));
+            }
+        }
+
+        let mut matches = vec![];
+
+        for (transform, variant) in variants {
+            if variant.width > self.width || variant.height > self.height {
+                continue;
+            }
+
+            for y in 0..=self.height - variant.height {
+                for x in 0..=self.width - variant.width {
+                    let origin = Point::new(x as i32, y as i32);
+
+                    let fits = variant
+                        .iter()
+                        .all(|(offset, &c)| c == wildcard || self.at(origin + offset) == Some(&c));
+
+                    if fits {
+                        matches.push(Match {
+                            origin,
+                            orientation: transform,
+                            cells: variant.positions().map(|offset| origin + offset).collect(),
+                        });
+                    }
+                }
+            }
+        }
+
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn transforms() {
+        let grid = Grid::from_str("abc\ndef").unwrap();
+
+        let expected = [
+            "abc\ndef",
+            "da\neb\nfc",
+            "fed\ncba",
+            "cf\nbe\nad",
+            "cba\nfed",
+            "def\nabc",
+            "ad\nbe\ncf",
+            "fc\neb\nda",
+        ];
+
+        for (transform, expected) in Transform::ALL.into_iter().zip(expected) {
+            assert_eq!(
+                grid.transformed(transform).to_string(),
+                expected,
+                "{transform:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn words_in_directions() {
+        let grid = Grid::from_str("CAT.\n.A..\nT.TC\nTAC.").unwrap();
+
+        let all = grid.find_word("CAT", &Direction::ALL, Wrap::None);
+        let found = all
+            .iter()
+            .map(|m| (m.origin, m.orientation))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            found,
+            vec![
+                (Point::new(0, 0), Direction::East),
+                (Point::new(0, 0), Direction::SouthEast),
+                (Point::new(2, 3), Direction::West),
+            ]
+        );
+        assert_eq!(
+            all[1].cells,
+            vec![Point::new(0, 0), Point::new(1, 1), Point::new(2, 2)]
+        );
+
+        // reading off the right edge comes back on the left
+        let wrapped = grid.find_word("CT", &[Direction::East], Wrap::Horizontal);
+        assert_eq!(wrapped.len(), 1);
+        assert_eq!(wrapped[0].cells, vec![Point::new(3, 2), Point::new(0, 2)]);
+        assert!(
+            grid.find_word("CT", &[Direction::East], Wrap::None)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn templates_with_wildcards() {
+        let grid = Grid::from_str("#..#\n##..\n...#\n..##").unwrap();
+        let corner = Grid::from_str("#?\n##").unwrap();
+
+        let found = grid.find_template(&corner, '?', &Transform::ALL);
+        let found = found
+            .iter()
+            .map(|m| (m.origin, m.orientation))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            found,
+            vec![
+                (Point::new(0, 0), Transform::Identity),
+                (Point::new(2, 2), Transform::Rotate270),
+            ]
+        );
+
+        let upright = grid.find_template(&corner, '?', &[Transform::Identity]);
+        assert_eq!(
+            upright[0].cells,
+            vec![
+                Point::new(0, 0),
+                Point::new(1, 0),
+                Point::new(0, 1),
+                Point::new(1, 1)
+            ]
+        );
+    }
+}
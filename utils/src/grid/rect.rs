@@ -0,0 +1,255 @@
+use super::{Grid, Point};
+
+/// An axis-aligned rectangle of cells between two inclusive corners.
+///
+/// A rectangle with no width or height is empty and contains no points.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rect {
+    /// The top-left corner.
+    pub min: Point,
+    /// The bottom-right corner, included in the rectangle.
+    pub max: Point,
+}
+
+impl Rect {
+    /// Create the rectangle with `a` and `b` as opposite corners, in any order.
+    pub fn new(a: Point, b: Point) -> Self {
+        Self {
+            min: Point::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Point::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    /// Create the rectangle `width` by `height` cells with its top-left corner
+    /// at `origin`.
+    pub fn from_size(origin: Point, width: usize, height: usize) -> Self {
+        Self {
+            min: origin,
+            max: origin + Point::new(width as i32 - 1, height as i32 - 1),
+        }
+    }
+
+    /// The smallest rectangle containing all the `points`, or `None` if there
+    /// aren't any.
+    pub fn bounding(points: impl IntoIterator<Item = Point>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+
+        Some(points.fold(Self::new(first, first), |rect, p| rect.include(p)))
+    }
+
+    /// Number of columns, or 0 if empty.
+    pub fn width(&self) -> usize {
+        span(self.min.x, self.max.x)
+    }
+
+    /// Number of rows, or 0 if empty.
+    pub fn height(&self) -> usize {
+        span(self.min.y, self.max.y)
+    }
+
+    /// Returns true if the rectangle contains no points.
+    pub fn is_empty(&self) -> bool {
+        self.width() == 0 || self.height() == 0
+    }
+
+    /// Number of cells in the rectangle, saturating at `u64::MAX` for the one
+    /// rectangle too big to count, covering every `Point`.
+    pub fn area(&self) -> u64 {
+        (self.width() as u64).saturating_mul(self.height() as u64)
+    }
+
+    /// Returns true if `p` is inside the rectangle.
+    pub fn contains(&self, p: Point) -> bool {
+        self.min.x <= p.x && p.x <= self.max.x && self.min.y <= p.y && p.y <= self.max.y
+    }
+
+    /// The cells in both rectangles, or `None` if they don't overlap.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let rect = Rect {
+            min: Point::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y)),
+            max: Point::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y)),
+        };
+
+        (!rect.is_empty()).then_some(rect)
+    }
+
+    /// The smallest rectangle containing both rectangles. An empty rectangle
+    /// adds nothing.
+    pub fn union(&self, other: &Rect) -> Rect {
+        match (self.is_empty(), other.is_empty()) {
+            (_, true) => *self,
+            (true, false) => *other,
+            (false, false) => Rect {
+                min: Point::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+                max: Point::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+            },
+        }
+    }
+
+    /// The smallest rectangle containing this one and `p`.
+    pub fn include(&self, p: Point) -> Rect {
+        self.union(&Rect::new(p, p))
+    }
+
+    /// Grow the rectangle by `n` cells on every side. Negative values shrink
+    /// it instead, which may leave it empty.
+    pub fn expand(&self, n: i32) -> Rect {
+        Rect {
+            min: self.min - Point::new(n, n),
+            max: self.max + Point::new(n, n),
+        }
+    }
+
+    /// Iterate over every point in reading order, left to right along each
+    /// row from the top.
+    pub fn points(&self) -> impl Iterator<Item = Point> + use<> {
+        let Rect { min, max } = *self;
+
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point::new(x, y)))
+    }
+
+    /// Iterate over the cells around the edge of the rectangle, once each,
+    /// clockwise from the top-left corner.
+    pub fn border(&self) -> impl Iterator<Item = Point> + use<> {
+        let Rect { min, max } = *self;
+        let (width, height) = (self.width(), self.height());
+
+        // the bottom row and left column would go back over the top row or
+        // right column when the rectangle is only one cell thick
+        let (bottom, left) = match width > 1 && height > 1 {
+            true => (min.x..max.x, min.y + 1..max.y),
+            false => (0..0, 0..0),
+        };
+
+        // an empty rectangle has an inverted range on one axis, so only the
+        // other one needs checking
+        (min.x..=max.x)
+            .filter(move |_| height > 0)
+            .map(move |x| Point::new(x, min.y))
+            .chain(
+                (min.y + 1..=max.y)
+                    .filter(move |_| width > 0)
+                    .map(move |y| Point::new(max.x, y)),
+            )
+            .chain(bottom.rev().map(move |x| Point::new(x, max.y)))
+            .chain(left.rev().map(move |y| Point::new(min.x, y)))
+    }
+}
+
+/// Number of values from `min` to `max` inclusive, worked out in `i64` so
+/// spans wider than `i32::MAX` don't overflow.
+fn span(min: i32, max: i32) -> usize {
+    (i64::from(max) - i64::from(min) + 1).max(0) as usize
+}
+
+impl<T> Grid<T> {
+    /// The rectangle covering every cell of the grid, with its top-left corner
+    /// at the origin.
+    pub fn bounds(&self) -> Rect {
+        Rect::from_size(Point::zero(), self.width, self.height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size_and_contains() {
+        let rect = Rect::new(Point::new(3, -1), Point::new(-2, 4));
+
+        assert_eq!(rect.min, Point::new(-2, -1));
+        assert_eq!(rect.max, Point::new(3, 4));
+        assert_eq!((rect.width(), rect.height(), rect.area()), (6, 6, 36));
+        assert!(rect.contains(Point::new(-2, 4)));
+        assert!(!rect.contains(Point::new(4, 0)));
+
+        let empty = Rect::from_size(Point::new(5, 5), 0, 3);
+        assert!(empty.is_empty());
+        assert_eq!(empty.area(), 0);
+        assert!(!empty.contains(Point::new(5, 5)));
+        assert_eq!(empty.points().count(), 0);
+        assert_eq!(empty.border().count(), 0);
+
+        let wide = Rect::new(Point::new(i32::MIN, 0), Point::new(i32::MAX, 0));
+        assert_eq!((wide.width(), wide.height()), (1 << 32, 1));
+        assert_eq!(wide.area(), 1 << 32);
+        assert!(wide.contains(Point::new(i32::MAX, 0)));
+
+        let everything = Rect::new(
+            Point::new(i32::MIN, i32::MIN),
+            Point::new(i32::MAX, i32::MAX),
+        );
+        assert_eq!(everything.area(), u64::MAX);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = Rect::new(Point::new(0, 0), Point::new(4, 2));
+        let b = Rect::new(Point::new(3, 1), Point::new(6, 5));
+
+        assert_eq!(
+            a.intersection(&b),
+            Some(Rect::new(Point::new(3, 1), Point::new(4, 2)))
+        );
+        assert_eq!(a.intersection(&a.expand(-1).expand(-1)), None);
+        assert_eq!(a.union(&b), Rect::new(Point::new(0, 0), Point::new(6, 5)));
+        assert_eq!(a.union(&Rect::from_size(Point::new(50, 50), 0, 0)), a);
+        assert_eq!(a.expand(2), Rect::new(Point::new(-2, -2), Point::new(6, 4)));
+        assert_eq!(
+            a.include(Point::new(-1, 7)),
+            Rect::new(Point::new(-1, 0), Point::new(4, 7))
+        );
+
+        let points = [Point::new(2, 9), Point::new(-4, 1), Point::new(0, 3)];
+        assert_eq!(
+            Rect::bounding(points),
+            Some(Rect::new(Point::new(-4, 1), Point::new(2, 9)))
+        );
+        assert_eq!(Rect::bounding([]), None);
+    }
+
+    #[test]
+    fn iteration() {
+        let rect = Rect::from_size(Point::new(1, 1), 3, 3);
+
+        assert_eq!(
+            rect.points().take(4).collect::<Vec<_>>(),
+            vec![
+                Point::new(1, 1),
+                Point::new(2, 1),
+                Point::new(3, 1),
+                Point::new(1, 2)
+            ]
+        );
+        assert_eq!(rect.points().count(), 9);
+
+        let border = rect.border().collect::<Vec<_>>();
+        assert_eq!(border.len(), 8);
+        assert_eq!(
+            border[..4],
+            [
+                Point::new(1, 1),
+                Point::new(2, 1),
+                Point::new(3, 1),
+                Point::new(3, 2)
+            ]
+        );
+        assert_eq!(border[7], Point::new(1, 2));
+        assert!(!border.contains(&Point::new(2, 2)));
+
+        // thin rectangles don't repeat any cells
+        assert_eq!(Rect::from_size(Point::zero(), 4, 1).border().count(), 4);
+        assert_eq!(Rect::from_size(Point::zero(), 1, 4).border().count(), 4);
+        assert_eq!(Rect::from_size(Point::zero(), 1, 1).border().count(), 1);
+    }
+
+    #[test]
+    fn grid_bounds() {
+        let grid = Grid::new(4, 2, '.');
+
+        assert_eq!(grid.bounds(), Rect::new(Point::new(0, 0), Point::new(3, 1)));
+        assert!(Grid::new(0, 0, ()).bounds().is_empty());
+    }
+}
@@ -0,0 +1,198 @@
+use super::{Grid, Neighbourhood, Point, Rect};
+use std::collections::VecDeque;
+
+/// A connected region of cells, as found by `Grid::flood_fill` or
+/// `Grid::label_components`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Region {
+    /// Every cell in the region, in the order the search reached them.
+    pub cells: Vec<Point>,
+    /// The box containing the region.
+    pub bounds: Rect,
+    /// Number of cell edges between the region and anything outside it,
+    /// including the edge of the grid.
+    pub perimeter: usize,
+}
+
+impl Region {
+    /// Number of cells in the region
+    pub fn size(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Build the stats for a region given its cells and a membership test.
+    fn new(cells: Vec<Point>, mut is_member: impl FnMut(Point) -> bool) -> Self {
+        let mut bounds = Rect::new(cells[0], cells[0]);
+        let mut perimeter = 0;
+
+        for &p in cells.iter() {
+            bounds = bounds.include(p);
+
+            perimeter += p
+                .neighbours4()
+                .into_iter()
+                .filter(|&n| !is_member(n))
+                .count();
+        }
+
+        Self {
+            cells,
+            bounds,
+            perimeter,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Find every cell reachable from `start` by orthogonal steps through cells
+    /// where `passable` returns true.
+    ///
+    /// Returns `None` if `start` is out of bounds or not passable itself.
+    pub fn flood_fill<F>(&self, start: Point, mut passable: F) -> Option<Region>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        if !passable(self.at(start)?) {
+            return None;
+        }
+
+        let mut seen = Grid::new(self.width, self.height, false);
+        let mut queue = VecDeque::new();
+        let mut cells = vec![];
+
+        *seen.at_mut(start).unwrap() = true;
+        queue.push_back(start);
+
+        while let Some(current) = queue.pop_front() {
+            cells.push(current);
+
+            for (next, cell) in self.neighbours4(current) {
+                let visited = seen.at_mut(next).unwrap();
+
+                if !*visited && passable(cell) {
+                    *visited = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        Some(Region::new(cells, |p| seen.at(p) == Some(&true)))
+    }
+
+    /// Split the whole grid into connected regions, where two adjacent cells in
+    /// the `neighbourhood` belong to the same region if `same_region` returns true
+    /// for them.
+    ///
+    /// Returns a grid holding the region label of each cell, plus the regions
+    /// themselves indexed by label. Labels are assigned in reading order of each
+    /// region's first cell. The perimeter of each region is always counted along
+    /// orthogonal edges, even if the neighbourhood includes diagonals.
+    pub fn label_components<F>(
+        &self,
+        neighbourhood: Neighbourhood<'_>,
+        mut same_region: F,
+    ) -> (Grid<usize>, Vec<Region>)
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        const UNLABELLED: usize = usize::MAX;
+
+        let mut labels = Grid::new(self.width, self.height, UNLABELLED);
+        let mut cells_by_label = vec![];
+        let mut queue = VecDeque::new();
+
+        for (start, _) in self.iter() {
+            if labels.at(start) != Some(&UNLABELLED) {
+                continue;
+            }
+
+            let label = cells_by_label.len();
+            let mut cells = vec![];
+
+            *labels.at_mut(start).unwrap() = label;
+            queue.push_back(start);
+
+            while let Some(current) = queue.pop_front() {
+                cells.push(current);
+                let cell = self.at(current).unwrap();
+
+                for (next, other) in self.neighbours(current, neighbourhood) {
+                    let next_label = labels.at_mut(next).unwrap();
+
+                    if *next_label == UNLABELLED && same_region(cell, other) {
+                        *next_label = label;
+                        queue.push_back(next);
+                    }
+                }
+            }
+
+            cells_by_label.push(cells);
+        }
+
+        let regions = cells_by_label
+            .into_iter()
+            .enumerate()
+            .map(|(label, cells)| Region::new(cells, |p| labels.at(p) == Some(&label)))
+            .collect();
+
+        (labels, regions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const MAP: &str = "\
+AAB
+ABB
+CCA";
+
+    #[test]
+    fn flood_fill_region_stats() {
+        let grid = Grid::from_str(MAP).unwrap();
+
+        let region = grid.flood_fill(Point::new(2, 1), |&c| c == 'B').unwrap();
+        assert_eq!(region.size(), 3);
+        assert_eq!(region.bounds, Rect::new(Point::new(1, 0), Point::new(2, 1)));
+        assert_eq!(region.perimeter, 8);
+        assert_eq!(region.cells[0], Point::new(2, 1));
+
+        assert_eq!(grid.flood_fill(Point::new(0, 0), |&c| c == 'B'), None);
+        assert_eq!(grid.flood_fill(Point::new(3, 0), |_| true), None);
+    }
+
+    #[test]
+    fn label_orthogonal_components() {
+        let grid = Grid::from_str(MAP).unwrap();
+        let (labels, regions) = grid.label_components(Neighbourhood::Orthogonal, |a, b| a == b);
+
+        assert_eq!(
+            labels,
+            Grid::from_rows(vec![vec![0, 0, 1], vec![0, 1, 1], vec![2, 2, 3]])
+        );
+
+        let sizes: Vec<usize> = regions.iter().map(|r| r.size()).collect();
+        assert_eq!(sizes, vec![3, 3, 2, 1]);
+
+        let perimeters: Vec<usize> = regions.iter().map(|r| r.perimeter).collect();
+        assert_eq!(perimeters, vec![8, 8, 6, 4]);
+    }
+
+    #[test]
+    fn label_diagonal_components() {
+        let grid = Grid::from_str(MAP).unwrap();
+        let (labels, regions) = grid.label_components(Neighbourhood::All, |a, b| a == b);
+
+        // the A in the bottom corner has no A anywhere around it, even diagonally
+        assert_eq!(regions.len(), 4);
+        assert_eq!(labels.at(Point::new(2, 2)), Some(&3));
+
+        let checkerboard = Grid::from_str("#.\n.#").unwrap();
+        let (_, regions) = checkerboard.label_components(Neighbourhood::All, |a, b| a == b);
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0].cells, vec![Point::new(0, 0), Point::new(1, 1)]);
+        assert_eq!(regions[0].perimeter, 8);
+    }
+}
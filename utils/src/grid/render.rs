@@ -0,0 +1,179 @@
+use super::{Grid, Point};
+use std::collections::HashSet;
+use std::fmt;
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Terminal colours available for highlighting cells with ANSI escape codes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Colour {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Colour {
+    /// The ANSI escape sequence which switches the foreground to this colour.
+    pub fn ansi(&self) -> &'static str {
+        match self {
+            Colour::Black => "\x1b[30m",
+            Colour::Red => "\x1b[31m",
+            Colour::Green => "\x1b[32m",
+            Colour::Yellow => "\x1b[33m",
+            Colour::Blue => "\x1b[34m",
+            Colour::Magenta => "\x1b[35m",
+            Colour::Cyan => "\x1b[36m",
+            Colour::White => "\x1b[37m",
+        }
+    }
+}
+
+/// A set of points to highlight in a single colour when rendering a grid,
+/// such as the path found by a search or the cells it visited.
+#[derive(Clone, Debug)]
+pub struct Layer {
+    points: HashSet<Point>,
+    colour: Colour,
+    symbol: Option<char>,
+}
+
+impl Layer {
+    /// Create a layer which draws `points` in the given colour.
+    pub fn new(points: impl IntoIterator<Item = Point>, colour: Colour) -> Self {
+        Self {
+            points: points.into_iter().collect(),
+            colour,
+            symbol: None,
+        }
+    }
+
+    /// Draw `symbol` in place of the underlying cell for every point in this layer.
+    pub fn with_symbol(mut self, symbol: char) -> Self {
+        self.symbol = Some(symbol);
+        self
+    }
+
+    /// Returns true if the layer covers `p`.
+    pub fn contains(&self, p: Point) -> bool {
+        self.points.contains(&p)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Render the grid to a string, using `f` to pick the char for each cell.
+    ///
+    /// Rows are separated by `\n` with no trailing newline.
+    pub fn render_with<F>(&self, mut f: F) -> String
+    where
+        F: FnMut(Point, &T) -> char,
+    {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                out.push('\n');
+            }
+
+            for (x, cell) in row.iter().enumerate() {
+                out.push(f(Point::new(x as i32, y as i32), cell));
+            }
+        }
+
+        out
+    }
+
+    /// Render the grid to a string like `render_with`, colouring any cell covered
+    /// by one of the overlay `layers` with ANSI escape codes. Where layers
+    /// overlap, the last one in the slice wins.
+    pub fn render_ansi<F>(&self, mut f: F, layers: &[Layer]) -> String
+    where
+        F: FnMut(Point, &T) -> char,
+    {
+        let mut out = String::new();
+
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                out.push('\n');
+            }
+
+            for (x, cell) in row.iter().enumerate() {
+                let p = Point::new(x as i32, y as i32);
+                let c = f(p, cell);
+
+                match layers.iter().rev().find(|l| l.contains(p)) {
+                    Some(layer) => {
+                        out.push_str(layer.colour.ansi());
+                        out.push(layer.symbol.unwrap_or(c));
+                        out.push_str(ANSI_RESET);
+                    }
+                    None => out.push(c),
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// Displays the grid exactly as it would be parsed, so `to_string()` output
+/// round-trips through `Grid::from_str`.
+impl fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render_with(|_, &c| c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn display_round_trips() {
+        let input = "#S..#\n#.#.#\n#..E#";
+        let grid = Grid::from_str(input).unwrap();
+
+        assert_eq!(grid.to_string(), input);
+        assert_eq!(Grid::from_str(&grid.to_string()).unwrap(), grid);
+    }
+
+    #[test]
+    fn ragged_display_round_trips() {
+        let grid = Grid::parse_ragged("ab\nc\n\ndef", '.');
+
+        assert_eq!(grid.to_string(), "ab.\nc..\n...\ndef");
+        assert_eq!(Grid::from_str(&grid.to_string()).unwrap(), grid);
+    }
+
+    #[test]
+    fn render_with_maps_cells() {
+        let grid = Grid::from_rows(vec![vec![1, 0], vec![0, 7]]);
+        let rendered = grid.render_with(|p, &v| if p.x == p.y { '\\' } else { (b'0' + v) as char });
+        assert_eq!(rendered, "\\0\n0\\");
+    }
+
+    #[test]
+    fn render_ansi_overlays() {
+        let grid = Grid::from_str("ab\ncd").unwrap();
+        let path = [Point::new(0, 0), Point::new(1, 0)];
+        let visited = [Point::new(1, 0), Point::new(1, 1)];
+
+        let rendered = grid.render_ansi(
+            |_, &c| c,
+            &[
+                Layer::new(path, Colour::Red),
+                Layer::new(visited, Colour::Blue).with_symbol('*'),
+            ],
+        );
+
+        assert_eq!(
+            rendered,
+            "\x1b[31ma\x1b[0m\x1b[34m*\x1b[0m\nc\x1b[34m*\x1b[0m"
+        );
+    }
+}
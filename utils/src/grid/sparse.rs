@@ -0,0 +1,312 @@
+use super::{Grid, Neighbourhood, Point, Rect};
+use std::collections::HashMap;
+use std::collections::hash_map;
+
+/// A grid of cells stored by `Point` rather than in rows, for maps which are
+/// mostly empty or have no fixed bounds. Coordinates may be negative.
+///
+/// The bounding box of all occupied cells is kept up to date as cells are
+/// inserted and removed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    bounds: Option<Rect>,
+}
+
+impl<T> SparseGrid<T> {
+    /// Create an empty grid.
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    /// Number of occupied cells
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns true if no cells are occupied.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Immutable access by `Point`. Returns `None` if the cell is empty.
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    /// Mutable access by `Point`. Returns `None` if the cell is empty.
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.cells.get_mut(&p)
+    }
+
+    /// Returns true if there is a value at `p`.
+    pub fn contains(&self, p: Point) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    /// Set the value at `p`, returning the previous value if there was one.
+    pub fn insert(&mut self, p: Point, value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            Some(bounds) => bounds.include(p),
+            None => Rect::new(p, p),
+        });
+
+        self.cells.insert(p, value)
+    }
+
+    /// Clear the cell at `p`, returning its value if there was one.
+    pub fn remove(&mut self, p: Point) -> Option<T> {
+        let removed = self.cells.remove(&p)?;
+
+        if let Some(Rect { min, max }) = self.bounds
+            && (p.x == min.x || p.y == min.y || p.x == max.x || p.y == max.y)
+        {
+            // the box may have shrunk, so it has to be rebuilt from scratch
+            self.bounds = Rect::bounding(self.cells.keys().copied());
+        }
+
+        Some(removed)
+    }
+
+    /// The box containing every occupied cell, or `None` if the grid is empty.
+    pub fn bounds(&self) -> Option<Rect> {
+        self.bounds
+    }
+
+    /// The top-left corner of the bounding box, or the origin if the grid is empty.
+    pub fn origin(&self) -> Point {
+        self.bounds
+            .map(|bounds| bounds.min)
+            .unwrap_or(Point::zero())
+    }
+
+    /// Width of the bounding box
+    pub fn width(&self) -> usize {
+        self.bounds.map(|bounds| bounds.width()).unwrap_or(0)
+    }
+
+    /// Height of the bounding box
+    pub fn height(&self) -> usize {
+        self.bounds.map(|bounds| bounds.height()).unwrap_or(0)
+    }
+
+    /// Iterate over the occupied cells in arbitrary order.
+    pub fn iter(&self) -> hash_map::Iter<'_, Point, T> {
+        self.cells.iter()
+    }
+
+    /// Iterate over the occupied points in arbitrary order.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        self.cells.keys().copied()
+    }
+
+    /// Iterate over the occupied orthogonal neighbours of `p` in NESW order.
+    pub fn neighbours4(&self, p: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.neighbours(p, Neighbourhood::Orthogonal)
+    }
+
+    /// Iterate over all 8 occupied neighbours of `p`.
+    pub fn neighbours8(&self, p: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.neighbours(p, Neighbourhood::All)
+    }
+
+    /// Iterate over the occupied neighbours of `p` in the given `Neighbourhood`.
+    pub fn neighbours<'a>(
+        &'a self,
+        p: Point,
+        neighbourhood: Neighbourhood<'a>,
+    ) -> impl Iterator<Item = (Point, &'a T)> {
+        p.neighbours(neighbourhood)
+            .filter_map(|n| self.get(n).map(|cell| (n, cell)))
+    }
+
+    /// Render the bounding box to a string, using `f` to pick the char for each
+    /// occupied cell and `empty` for the rest.
+    pub fn render_with<F>(&self, empty: char, mut f: F) -> String
+    where
+        F: FnMut(Point, &T) -> char,
+    {
+        let Some(Rect { min, max }) = self.bounds else {
+            return String::new();
+        };
+
+        let mut out = String::with_capacity((self.width() + 1) * self.height());
+
+        for y in min.y..=max.y {
+            if y > min.y {
+                out.push('\n');
+            }
+
+            for x in min.x..=max.x {
+                let p = Point::new(x, y);
+                out.push(self.get(p).map_or(empty, |cell| f(p, cell)));
+            }
+        }
+
+        out
+    }
+
+    /// Convert to a dense grid covering the bounding box, with `fill` in every
+    /// empty cell. Cell `(0, 0)` of the dense grid is `origin()`.
+    pub fn to_grid(&self, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.dense(|cell| cell.cloned().unwrap_or_else(|| fill.clone()))
+    }
+
+    /// Convert to a dense grid covering the bounding box, with empty cells as
+    /// `None`. Cell `(0, 0)` of the dense grid is `origin()`, so passing both
+    /// back to `from_option_grid` gives an identical sparse grid.
+    pub fn to_option_grid(&self) -> Grid<Option<T>>
+    where
+        T: Clone,
+    {
+        self.dense(|cell| cell.cloned())
+    }
+
+    /// Build a sparse grid from the `Some` cells of a dense grid whose `(0, 0)`
+    /// cell is at `origin`.
+    pub fn from_option_grid(grid: Grid<Option<T>>, origin: Point) -> Self {
+        let mut sparse = Self::new();
+
+        for (y, row) in grid.into_iter().enumerate() {
+            for (x, cell) in row.into_iter().enumerate() {
+                if let Some(value) = cell {
+                    sparse.insert(origin + Point::new(x as i32, y as i32), value);
+                }
+            }
+        }
+
+        sparse
+    }
+
+    fn dense<U, F>(&self, mut f: F) -> Grid<U>
+    where
+        F: FnMut(Option<&T>) -> U,
+    {
+        let Some(Rect { min, max }) = self.bounds else {
+            return Grid::from_rows(vec![]);
+        };
+
+        Grid::from_rows(
+            (min.y..=max.y)
+                .map(|y| {
+                    (min.x..=max.x)
+                        .map(|x| f(self.get(Point::new(x, y))))
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Every cell of the dense grid becomes an occupied cell at the same position.
+impl<T> From<Grid<T>> for SparseGrid<T> {
+    fn from(grid: Grid<T>) -> Self {
+        let mut sparse = Self::new();
+
+        for (y, row) in grid.into_iter().enumerate() {
+            for (x, cell) in row.into_iter().enumerate() {
+                sparse.insert(Point::new(x as i32, y as i32), cell);
+            }
+        }
+
+        sparse
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut sparse = Self::new();
+
+        for (p, value) in iter {
+            sparse.insert(p, value);
+        }
+
+        sparse
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_track_inserts_and_removes() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+
+        grid.insert(Point::new(-3, 2), 'a');
+        grid.insert(Point::new(4, -1), 'b');
+        grid.insert(Point::new(0, 0), 'c');
+        assert_eq!(
+            grid.bounds(),
+            Some(Rect::new(Point::new(-3, -1), Point::new(4, 2)))
+        );
+        assert_eq!((grid.width(), grid.height()), (8, 4));
+
+        assert_eq!(grid.remove(Point::new(4, -1)), Some('b'));
+        assert_eq!(
+            grid.bounds(),
+            Some(Rect::new(Point::new(-3, 0), Point::new(0, 2)))
+        );
+        assert_eq!(grid.remove(Point::new(4, -1)), None);
+
+        grid.remove(Point::new(-3, 2));
+        grid.remove(Point::new(0, 0));
+        assert!(grid.is_empty());
+        assert_eq!(grid.bounds(), None);
+    }
+
+    #[test]
+    fn neighbours_and_rendering() {
+        let grid: SparseGrid<char> = [(-1, -1), (0, -1), (1, 0), (-1, 1)]
+            .into_iter()
+            .map(|(x, y)| (Point::new(x, y), '#'))
+            .collect();
+
+        let n4: Vec<Point> = grid.neighbours4(Point::new(0, 0)).map(|(p, _)| p).collect();
+        assert_eq!(n4, vec![Point::new(0, -1), Point::new(1, 0)]);
+        assert_eq!(grid.neighbours8(Point::new(0, 0)).count(), 4);
+
+        assert_eq!(grid.render_with('.', |_, &c| c), "##.\n..#\n#..");
+    }
+
+    #[test]
+    fn dense_round_trip() {
+        let mut sparse = SparseGrid::new();
+        sparse.insert(Point::new(-2, -2), 1);
+        sparse.insert(Point::new(0, -1), 2);
+
+        let dense = sparse.to_option_grid();
+        assert_eq!(
+            dense,
+            Grid::from_rows(vec![vec![Some(1), None, None], vec![None, None, Some(2)]])
+        );
+        assert_eq!(
+            sparse.to_grid(0),
+            Grid::from_rows(vec![vec![1, 0, 0], vec![0, 0, 2]])
+        );
+
+        let back = SparseGrid::from_option_grid(dense, sparse.origin());
+        assert_eq!(back, sparse);
+
+        let full = SparseGrid::from(Grid::from_rows(vec![vec![5, 6], vec![7, 8]]));
+        assert_eq!(full.len(), 4);
+        assert_eq!(full.get(Point::new(1, 1)), Some(&8));
+        assert_eq!(
+            full.to_grid(0),
+            Grid::from_rows(vec![vec![5, 6], vec![7, 8]])
+        );
+    }
+}
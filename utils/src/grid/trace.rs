@@ -0,0 +1,231 @@
+use super::{Direction, Grid, Point};
+use std::error::Error;
+use std::fmt;
+
+/// A path followed through a grid by `Grid::trace_path`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trace<'a, T> {
+    /// The points along the path in order.
+    pub points: Vec<Point>,
+    /// The cell at each point along the path.
+    pub cells: Vec<&'a T>,
+    /// True if the path is a loop, i.e. the last point is next to the first.
+    pub closed: bool,
+}
+
+/// Reasons a path couldn't be traced by `Grid::trace_path`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraceError {
+    /// The start point is outside the grid or isn't passable.
+    BlockedStart(Point),
+    /// The first step in the requested heading isn't passable.
+    BlockedHeading(Point, Direction),
+    /// The requested heading is diagonal, but paths only take orthogonal steps.
+    DiagonalHeading(Direction),
+    /// The path splits, so there is more than one way to continue from `at`.
+    Branch { at: Point, options: Vec<Point> },
+}
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceError::BlockedStart(p) => write!(f, "start {p} is not on the path"),
+            TraceError::BlockedHeading(p, dir) => {
+                write!(f, "no path leading {dir:?} from start {p}")
+            }
+            TraceError::DiagonalHeading(dir) => {
+                write!(f, "can't trace a path heading {dir:?}")
+            }
+            TraceError::Branch { at, options } => {
+                write!(f, "path branches at {at} into {} directions", options.len())
+            }
+        }
+    }
+}
+
+impl Error for TraceError {}
+
+impl<T> Grid<T> {
+    /// Follow a one cell wide path of `passable` cells from `start`, taking
+    /// orthogonal steps, and return every point along it in order.
+    ///
+    /// If `heading` is given the first step is taken in that direction, which
+    /// must be orthogonal or a `TraceError::DiagonalHeading` is returned.
+    /// Otherwise the path is followed whichever way it goes from `start`. When
+    /// `start` is in the middle of an open path, both halves are followed and
+    /// the result runs from one end to the other.
+    ///
+    /// Tracing stops at a dead end, or when the path arrives back next to
+    /// `start` to close a loop. Reaching a cell with more than one unvisited
+    /// way forward is reported as a `TraceError::Branch`.
+    pub fn trace_path<F>(
+        &self,
+        start: Point,
+        mut passable: F,
+        heading: Option<Direction>,
+    ) -> Result<Trace<'_, T>, TraceError>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        if let Some(dir) = heading.filter(Direction::is_diagonal) {
+            return Err(TraceError::DiagonalHeading(dir));
+        }
+
+        let mut is_open = |p: Point| self.at(p).is_some_and(&mut passable);
+
+        if !is_open(start) {
+            return Err(TraceError::BlockedStart(start));
+        }
+
+        let mut visited = Grid::new(self.width, self.height, false);
+        *visited.at_mut(start).unwrap() = true;
+
+        let exits: Vec<Point> = match heading {
+            Some(dir) if is_open(start.step(dir)) => vec![start.step(dir)],
+            Some(dir) => return Err(TraceError::BlockedHeading(start, dir)),
+            None => start
+                .neighbours4()
+                .into_iter()
+                .filter(|&p| is_open(p))
+                .collect(),
+        };
+
+        if exits.len() > 2 {
+            return Err(TraceError::Branch {
+                at: start,
+                options: exits,
+            });
+        }
+
+        let mut points = vec![start];
+        let mut closed = false;
+
+        if let Some(&first) = exits.first() {
+            let (forwards, is_loop) = follow(start, first, &mut visited, &mut is_open)?;
+            points.extend(forwards);
+            closed = is_loop;
+        }
+
+        if !closed && let Some(&second) = exits.get(1) {
+            // the start was part way along an open path, so prepend the other half
+            let (backwards, _) = follow(start, second, &mut visited, &mut is_open)?;
+            points.splice(0..0, backwards.into_iter().rev());
+        }
+
+        let cells = points.iter().map(|&p| self.at(p).unwrap()).collect();
+
+        Ok(Trace {
+            points,
+            cells,
+            closed,
+        })
+    }
+}
+
+/// Walk from `next` until the path ends, returning the points visited and
+/// whether the walk finished next to `start`.
+fn follow(
+    start: Point,
+    mut next: Point,
+    visited: &mut Grid<bool>,
+    is_open: &mut impl FnMut(Point) -> bool,
+) -> Result<(Vec<Point>, bool), TraceError> {
+    let mut points = vec![];
+
+    loop {
+        *visited.at_mut(next).unwrap() = true;
+        points.push(next);
+
+        let options: Vec<Point> = next
+            .neighbours4()
+            .into_iter()
+            .filter(|&p| visited.at(p) == Some(&false) && is_open(p))
+            .collect();
+
+        match options.as_slice() {
+            [] => {
+                let closed = points.len() > 1 && next.manhattan_distance(start) == 1;
+                return Ok((points, closed));
+            }
+            [only] => next = *only,
+            _ => return Err(TraceError::Branch { at: next, options }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn trace_closed_loop_with_heading() {
+        let grid = Grid::from_str("S+===\n-   +\n=+=-+").unwrap();
+        let trace = grid
+            .trace_path(Point::new(0, 0), |&c| c != ' ', Some(Direction::East))
+            .unwrap();
+
+        assert!(trace.closed);
+        assert_eq!(trace.points.len(), 12);
+        assert_eq!(trace.points[1], Point::new(1, 0));
+        assert_eq!(trace.points[11], Point::new(0, 1));
+
+        let track: String = trace.cells.into_iter().collect();
+        assert_eq!(track, "S+===++-=+=-");
+    }
+
+    #[test]
+    fn trace_open_path_from_the_middle() {
+        let grid = Grid::from_str("#....\n###.#\n..#.#").unwrap();
+        let trace = grid
+            .trace_path(Point::new(1, 1), |&c| c == '#', None)
+            .unwrap();
+
+        assert!(!trace.closed);
+        assert_eq!(
+            trace.points,
+            vec![
+                Point::new(0, 0),
+                Point::new(0, 1),
+                Point::new(1, 1),
+                Point::new(2, 1),
+                Point::new(2, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn trace_errors() {
+        let grid = Grid::from_str(".#.\n###\n.#.").unwrap();
+        let wall = |c: &char| *c == '#';
+
+        assert_eq!(
+            grid.trace_path(Point::new(0, 0), wall, None),
+            Err(TraceError::BlockedStart(Point::new(0, 0)))
+        );
+        assert_eq!(
+            grid.trace_path(Point::new(1, 0), wall, Some(Direction::West)),
+            Err(TraceError::BlockedHeading(
+                Point::new(1, 0),
+                Direction::West
+            ))
+        );
+        assert_eq!(
+            grid.trace_path(Point::new(1, 0), wall, None),
+            Err(TraceError::Branch {
+                at: Point::new(1, 1),
+                options: vec![Point::new(2, 1), Point::new(1, 2), Point::new(0, 1)],
+            })
+        );
+    }
+
+    #[test]
+    fn trace_diagonal_heading() {
+        let grid = Grid::from_str("#.\n.#").unwrap();
+
+        assert_eq!(
+            grid.trace_path(Point::new(0, 0), |&c| c == '#', Some(Direction::SouthEast)),
+            Err(TraceError::DiagonalHeading(Direction::SouthEast))
+        );
+    }
+}
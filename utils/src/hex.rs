@@ -0,0 +1,437 @@
+use crate::grid::{Grid, Point};
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+
+/// A hexagon in axial coordinates.
+///
+/// The third cube coordinate is implied as `s = -q - r`. Moving along `q`
+/// heads east and moving along `r` heads south-east (pointy-top) or south
+/// (flat-top), matching the usual `y` increasing downward convention.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Hex {
+    /// The 6 neighbour offsets, in clockwise order starting from `+q`.
+    pub const DIRECTIONS: [Hex; 6] = [
+        Hex::new(1, 0),
+        Hex::new(0, 1),
+        Hex::new(-1, 1),
+        Hex::new(-1, 0),
+        Hex::new(0, -1),
+        Hex::new(1, -1),
+    ];
+
+    /// Create a new `Hex` from axial coordinates.
+    pub const fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    /// The origin hex `(0, 0)`.
+    pub fn zero() -> Self {
+        Self::new(0, 0)
+    }
+
+    /// Create a `Hex` from cube coordinates. Panics if they don't sum to zero.
+    pub fn from_cube(q: i32, r: i32, s: i32) -> Self {
+        assert_eq!(q + r + s, 0, "Cube coordinates must sum to zero");
+        Self::new(q, r)
+    }
+
+    /// The implied third cube coordinate.
+    pub fn s(&self) -> i32 {
+        -self.q - self.r
+    }
+
+    /// Return the 6 adjacent hexes in the same order as `DIRECTIONS`.
+    pub fn neighbours(&self) -> [Self; 6] {
+        Self::DIRECTIONS.map(|d| *self + d)
+    }
+
+    /// Number of steps between two hexes.
+    pub fn distance(&self, other: Hex) -> u32 {
+        let d = *self - other;
+        (d.q.unsigned_abs() + d.r.unsigned_abs() + d.s().unsigned_abs()) / 2
+    }
+
+    /// Rotate around `centre` by `turns` sixths of a full turn. Positive turns
+    /// are clockwise, negative ones anticlockwise.
+    pub fn rotate_around(&self, centre: Hex, turns: i32) -> Self {
+        let mut d = *self - centre;
+
+        for _ in 0..turns.rem_euclid(6) {
+            d = Hex::from_cube(-d.r, -d.s(), -d.q);
+        }
+
+        centre + d
+    }
+
+    /// Every hex exactly `radius` steps away, walking clockwise from the one
+    /// in the `-r` direction. A radius of 0 is just this hex.
+    pub fn ring(&self, radius: u32) -> Vec<Hex> {
+        if radius == 0 {
+            return vec![*self];
+        }
+
+        let mut ring = Vec::with_capacity(6 * radius as usize);
+        let mut current = *self + Self::DIRECTIONS[4] * radius as i32;
+
+        for direction in Self::DIRECTIONS {
+            for _ in 0..radius {
+                ring.push(current);
+                current += direction;
+            }
+        }
+
+        ring
+    }
+
+    /// Every hex within `radius` steps, starting with this one and then each
+    /// ring in turn outwards.
+    pub fn spiral(&self, radius: u32) -> Vec<Hex> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+
+    /// Position of this hex when stored in a grid with the given `layout`.
+    pub fn to_offset(&self, layout: OffsetLayout) -> Point {
+        let (q, r) = (self.q, self.r);
+
+        match layout {
+            OffsetLayout::OddR => Point::new(q + (r - (r & 1)) / 2, r),
+            OffsetLayout::EvenR => Point::new(q + (r + (r & 1)) / 2, r),
+            OffsetLayout::OddQ => Point::new(q, r + (q - (q & 1)) / 2),
+            OffsetLayout::EvenQ => Point::new(q, r + (q + (q & 1)) / 2),
+        }
+    }
+
+    /// The hex stored at grid position `p` in the given `layout`.
+    pub fn from_offset(p: Point, layout: OffsetLayout) -> Self {
+        let (x, y) = (p.x, p.y);
+
+        match layout {
+            OffsetLayout::OddR => Hex::new(x - (y - (y & 1)) / 2, y),
+            OffsetLayout::EvenR => Hex::new(x - (y + (y & 1)) / 2, y),
+            OffsetLayout::OddQ => Hex::new(x, y - (x - (x & 1)) / 2),
+            OffsetLayout::EvenQ => Hex::new(x, y - (x + (x & 1)) / 2),
+        }
+    }
+}
+
+impl fmt::Display for Hex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.q, self.r, self.s())
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, rhs: Hex) -> Hex {
+        Hex::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, rhs: Hex) -> Hex {
+        Hex::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl AddAssign for Hex {
+    fn add_assign(&mut self, rhs: Hex) {
+        self.q += rhs.q;
+        self.r += rhs.r;
+    }
+}
+
+impl SubAssign for Hex {
+    fn sub_assign(&mut self, rhs: Hex) {
+        self.q -= rhs.q;
+        self.r -= rhs.r;
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Hex;
+
+    fn mul(self, rhs: i32) -> Hex {
+        Hex::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+/// How hexes are packed into the rows and columns of a rectangular grid.
+///
+/// The `R` layouts are for pointy-top hexes stored in rows, with every odd or
+/// even row shoved half a hex to the right. The `Q` layouts are for flat-top
+/// hexes stored in columns, with every odd or even column shoved half a hex down.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OffsetLayout {
+    OddR,
+    EvenR,
+    OddQ,
+    EvenQ,
+}
+
+impl OffsetLayout {
+    /// Returns true for the pointy-top (row based) layouts.
+    pub fn is_pointy(&self) -> bool {
+        matches!(self, OffsetLayout::OddR | OffsetLayout::EvenR)
+    }
+
+    /// Returns true if the given row (pointy-top) or column (flat-top) is the
+    /// one shoved across by half a hex.
+    fn is_shifted(&self, index: usize) -> bool {
+        match self {
+            OffsetLayout::OddR | OffsetLayout::OddQ => !index.is_multiple_of(2),
+            OffsetLayout::EvenR | OffsetLayout::EvenQ => index.is_multiple_of(2),
+        }
+    }
+}
+
+/// A hex map stored in a rectangular `Grid` using an `OffsetLayout`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HexGrid<T> {
+    grid: Grid<T>,
+    layout: OffsetLayout,
+}
+
+impl<T> HexGrid<T> {
+    /// Wrap a grid whose cells are stored in the given `layout`.
+    pub fn new(grid: Grid<T>, layout: OffsetLayout) -> Self {
+        Self { grid, layout }
+    }
+
+    /// The layout used to store the hexes.
+    pub fn layout(&self) -> OffsetLayout {
+        self.layout
+    }
+
+    /// The underlying rectangular grid.
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    /// Consume the hex grid and return the underlying rectangular grid.
+    pub fn into_grid(self) -> Grid<T> {
+        self.grid
+    }
+
+    /// Immutable access by `Hex`. Returns `None` if outside the grid.
+    pub fn at(&self, hex: Hex) -> Option<&T> {
+        self.grid.at(hex.to_offset(self.layout))
+    }
+
+    /// Mutable access by `Hex`. Returns `None` if outside the grid.
+    pub fn at_mut(&mut self, hex: Hex) -> Option<&mut T> {
+        self.grid.at_mut(hex.to_offset(self.layout))
+    }
+
+    /// Iterate over every cell along with its `Hex`, in the grid's reading order.
+    pub fn iter(&self) -> impl Iterator<Item = (Hex, &T)> {
+        self.grid
+            .iter()
+            .map(|(p, cell)| (Hex::from_offset(p, self.layout), cell))
+    }
+
+    /// Iterate over the in-bounds neighbours of `hex` along with their cells.
+    pub fn neighbours(&self, hex: Hex) -> impl Iterator<Item = (Hex, &T)> {
+        hex.neighbours()
+            .into_iter()
+            .filter_map(|n| self.at(n).map(|cell| (n, cell)))
+    }
+}
+
+impl HexGrid<char> {
+    /// Parse a hex map drawn as text.
+    ///
+    /// Pointy-top maps have one line per row with cells separated by a space,
+    /// and each shifted row indented by one space:
+    ///
+    /// ```text
+    /// a b c
+    ///  d e f
+    /// ```
+    ///
+    /// Flat-top maps have one char per column, with each row spread over two
+    /// lines so that the shifted columns sit on the lines in between:
+    ///
+    /// ```text
+    /// a c
+    ///  b d
+    /// ```
+    ///
+    /// Missing cells at the end of short lines are filled with spaces.
+    pub fn parse(s: &str, layout: OffsetLayout) -> Self {
+        let lines: Vec<Vec<char>> = s.lines().map(|l| l.chars().collect()).collect();
+
+        let rows = if layout.is_pointy() {
+            lines
+                .iter()
+                .enumerate()
+                .map(|(y, line)| {
+                    let indent = layout.is_shifted(y) as usize;
+                    line.iter().skip(indent).step_by(2).copied().collect()
+                })
+                .collect()
+        } else {
+            let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+            let height = lines.len().div_ceil(2);
+
+            (0..height)
+                .map(|y| {
+                    (0..width)
+                        .map(|x| {
+                            let line = 2 * y + layout.is_shifted(x) as usize;
+                            lines
+                                .get(line)
+                                .and_then(|l| l.get(x))
+                                .copied()
+                                .unwrap_or(' ')
+                        })
+                        .collect()
+                })
+                .collect()
+        };
+
+        Self::new(Grid::from_rows_padded(rows, ' '), layout)
+    }
+}
+
+/// Draws the map in the same format accepted by `HexGrid::parse`, with
+/// trailing spaces trimmed from each line.
+impl fmt::Display for HexGrid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines: Vec<String> = vec![];
+
+        if self.layout.is_pointy() {
+            for (y, row) in self.grid.rows().enumerate() {
+                let indent = if self.layout.is_shifted(y) { " " } else { "" };
+                let cells: Vec<String> = row.iter().map(|c| c.to_string()).collect();
+                lines.push(format!("{indent}{}", cells.join(" ")));
+            }
+        } else {
+            let mut text = vec![vec![' '; self.grid.width()]; 2 * self.grid.height()];
+
+            for (p, &c) in self.grid.iter() {
+                let (x, y) = (p.x as usize, p.y as usize);
+                text[2 * y + self.layout.is_shifted(x) as usize][x] = c;
+            }
+
+            lines.extend(text.into_iter().map(|l| l.into_iter().collect()));
+        }
+
+        for line in lines.iter_mut() {
+            line.truncate(line.trim_end().len());
+        }
+
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+
+        f.write_str(&lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAYOUTS: [OffsetLayout; 4] = [
+        OffsetLayout::OddR,
+        OffsetLayout::EvenR,
+        OffsetLayout::OddQ,
+        OffsetLayout::EvenQ,
+    ];
+
+    #[test]
+    fn distances_rings_and_spirals() {
+        let centre = Hex::new(2, -1);
+
+        assert_eq!(centre.distance(centre), 0);
+        assert!(centre.neighbours().iter().all(|n| n.distance(centre) == 1));
+        assert_eq!(Hex::new(0, 0).distance(Hex::new(3, -5)), 5);
+
+        for radius in 0..5 {
+            let ring = centre.ring(radius);
+            assert_eq!(ring.len(), (6 * radius).max(1) as usize);
+            assert!(ring.iter().all(|h| h.distance(centre) == radius));
+        }
+
+        let spiral = centre.spiral(3);
+        assert_eq!(spiral.len(), 1 + 3 * 3 * 4);
+        assert_eq!(spiral[0], centre);
+    }
+
+    #[test]
+    fn rotation() {
+        let centre = Hex::new(1, 1);
+        let h = Hex::new(3, 0);
+
+        assert_eq!(h.rotate_around(centre, 6), h);
+        assert_eq!(h.rotate_around(centre, -1), h.rotate_around(centre, 5));
+        assert_eq!(
+            h.rotate_around(centre, 1).distance(centre),
+            h.distance(centre)
+        );
+
+        // clockwise follows the neighbour order
+        let east = Hex::zero() + Hex::DIRECTIONS[0];
+        assert_eq!(east.rotate_around(Hex::zero(), 1), Hex::DIRECTIONS[1]);
+        assert_eq!(east.rotate_around(Hex::zero(), 3), Hex::DIRECTIONS[3]);
+    }
+
+    #[test]
+    fn offset_round_trip() {
+        for layout in LAYOUTS {
+            for y in -4..4 {
+                for x in -4..4 {
+                    let p = Point::new(x, y);
+                    assert_eq!(Hex::from_offset(p, layout).to_offset(layout), p);
+                }
+            }
+        }
+
+        // odd rows are shoved right, so (0, 1) sits below and right of (0, 0)
+        let below = Hex::from_offset(Point::new(0, 1), OffsetLayout::OddR);
+        assert!(Hex::zero().neighbours().contains(&below));
+    }
+
+    #[test]
+    fn pointy_top_parse_and_render() {
+        let text = "a b c\n d e f\ng h i";
+        let map = HexGrid::parse(text, OffsetLayout::OddR);
+
+        assert_eq!(map.grid().width(), 3);
+        assert_eq!(map.to_string(), text);
+
+        let e = Hex::from_offset(Point::new(1, 1), OffsetLayout::OddR);
+        let mut around: Vec<char> = map.neighbours(e).map(|(_, &c)| c).collect();
+        around.sort();
+        assert_eq!(around, vec!['b', 'c', 'd', 'f', 'h', 'i']);
+    }
+
+    #[test]
+    fn flat_top_parse_and_render() {
+        let text = "a c\n b d\ne g\n f h";
+        let map = HexGrid::parse(text, OffsetLayout::OddQ);
+
+        assert_eq!((map.grid().width(), map.grid().height()), (4, 2));
+        assert_eq!(map.grid().at(Point::new(1, 0)), Some(&'b'));
+        assert_eq!(map.grid().at(Point::new(2, 1)), Some(&'g'));
+        assert_eq!(map.to_string(), text);
+
+        let b = Hex::from_offset(Point::new(1, 0), OffsetLayout::OddQ);
+        let mut around: Vec<char> = map.neighbours(b).map(|(_, &c)| c).collect();
+        around.sort();
+        assert_eq!(around, vec!['a', 'c', 'e', 'f', 'g']);
+
+        let even = HexGrid::parse(" b\na c", OffsetLayout::EvenQ);
+        assert_eq!(even.grid().at(Point::new(0, 0)), Some(&'a'));
+        assert_eq!(even.grid().at(Point::new(1, 0)), Some(&'b'));
+        assert_eq!(even.to_string(), " b\na c");
+    }
+}
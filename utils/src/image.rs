@@ -0,0 +1,360 @@
+use crate::grid::{Grid, Point};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// An RGB colour with 8 bits per channel.
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+
+/// Simple in-memory RGB image which can be written out as PPM or PNG without
+/// any external dependencies.
+///
+/// Images are built from grids or point sets, with each cell drawn as a
+/// `scale` x `scale` block of pixels so small maps are still visible.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Create a new image filled with the `background` colour.
+    pub fn new(width: usize, height: usize, background: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    /// Width in pixels
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height in pixels
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The colour of the pixel at `(x, y)`, or `None` if out of bounds.
+    pub fn pixel(&self, x: usize, y: usize) -> Option<Rgb> {
+        (x < self.width && y < self.height).then(|| self.pixels[y * self.width + x])
+    }
+
+    /// Set the colour of the pixel at `(x, y)`. Out of bounds pixels are ignored.
+    pub fn set_pixel(&mut self, x: usize, y: usize, colour: Rgb) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = colour;
+        }
+    }
+
+    /// Draw a grid, using `colour` to pick the colour of each cell.
+    pub fn from_grid<T, F>(grid: &Grid<T>, scale: usize, mut colour: F) -> Self
+    where
+        F: FnMut(Point, &T) -> Rgb,
+    {
+        let mut image = Self::new(grid.width() * scale, grid.height() * scale, BLACK);
+
+        for (p, cell) in grid.iter() {
+            image.fill_cell(p.x as usize, p.y as usize, scale, colour(p, cell));
+        }
+
+        image
+    }
+
+    /// Draw a grid as a heatmap, using `value` to turn each cell into a
+    /// number. Values are scaled between the smallest and largest in the grid
+    /// from blue (cold) to red (hot).
+    pub fn heatmap<T, F>(grid: &Grid<T>, scale: usize, value: F) -> Self
+    where
+        F: Fn(&T) -> f64,
+    {
+        let (min, max) = grid
+            .iter()
+            .map(|(_, v)| value(v))
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+                (lo.min(v), hi.max(v))
+            });
+
+        let range = max - min;
+
+        Self::from_grid(grid, scale, |_, v| {
+            let t = if range > 0.0 {
+                (value(v) - min) / range
+            } else {
+                0.0
+            };
+
+            heat(t)
+        })
+    }
+
+    /// Draw a set of points in the `foreground` colour on a `background` sized
+    /// to their bounding box. The top-left pixel is the smallest x and y.
+    pub fn from_points(
+        points: impl IntoIterator<Item = Point>,
+        scale: usize,
+        foreground: Rgb,
+        background: Rgb,
+    ) -> Self {
+        let points: Vec<Point> = points.into_iter().collect();
+
+        let Some(min_x) = points.iter().map(|p| p.x).min() else {
+            return Self::new(0, 0, background);
+        };
+        let min_y = points.iter().map(|p| p.y).min().unwrap();
+        let max_x = points.iter().map(|p| p.x).max().unwrap();
+        let max_y = points.iter().map(|p| p.y).max().unwrap();
+
+        let width = (max_x - min_x) as usize + 1;
+        let height = (max_y - min_y) as usize + 1;
+        let mut image = Self::new(width * scale, height * scale, background);
+
+        for p in points {
+            let x = (p.x - min_x) as usize;
+            let y = (p.y - min_y) as usize;
+            image.fill_cell(x, y, scale, foreground);
+        }
+
+        image
+    }
+
+    fn fill_cell(&mut self, x: usize, y: usize, scale: usize, colour: Rgb) {
+        for py in y * scale..(y + 1) * scale {
+            for px in x * scale..(x + 1) * scale {
+                self.set_pixel(px, py, colour);
+            }
+        }
+    }
+
+    /// Write the image in binary PPM (P6) format.
+    pub fn write_ppm<W: Write>(&self, mut w: W) -> io::Result<()> {
+        write!(w, "P6\n{} {}\n255\n", self.width, self.height)?;
+
+        for pixel in &self.pixels {
+            w.write_all(pixel)?;
+        }
+
+        w.flush()
+    }
+
+    /// Write the image as a truecolour PNG. The image data is stored without
+    /// compression, so files are large but need no external dependencies.
+    pub fn write_png<W: Write>(&self, mut w: W) -> io::Result<()> {
+        const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        header.extend_from_slice(&[8, 2, 0, 0, 0]); // 8-bit RGB, no interlace
+
+        // every scanline starts with a filter type byte, 0 = no filter
+        let mut raw = Vec::with_capacity(self.height * (self.width * 3 + 1));
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            raw.push(0);
+            raw.extend(row.iter().flatten());
+        }
+
+        w.write_all(&SIGNATURE)?;
+        write_chunk(&mut w, b"IHDR", &header)?;
+        write_chunk(&mut w, b"IDAT", &zlib_stored(&raw))?;
+        write_chunk(&mut w, b"IEND", &[])?;
+
+        w.flush()
+    }
+
+    /// Save the image to `path`, choosing PNG or PPM from the file extension.
+    /// Anything other than `.png` is written as PPM.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let file = BufWriter::new(File::create(path)?);
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("png") => self.write_png(file),
+            _ => self.write_ppm(file),
+        }
+    }
+}
+
+/// Map `t` in the range `0.0..=1.0` onto a blue, cyan, green, yellow, red
+/// heatmap palette. Values outside the range are clamped.
+pub fn heat(t: f64) -> Rgb {
+    const STOPS: [Rgb; 5] = [
+        [0, 0, 255],
+        [0, 255, 255],
+        [0, 255, 0],
+        [255, 255, 0],
+        [255, 0, 0],
+    ];
+
+    let scaled = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f64;
+    let i = (scaled.floor() as usize).min(STOPS.len() - 2);
+    let frac = scaled - i as f64;
+
+    let (from, to) = (STOPS[i], STOPS[i + 1]);
+    let mut colour = BLACK;
+
+    for c in 0..3 {
+        let v = from[c] as f64 + (to[c] as f64 - from[c] as f64) * frac;
+        colour[c] = v.round() as u8;
+    }
+
+    colour
+}
+
+/// Write a single PNG chunk: length, type, data and CRC.
+fn write_chunk<W: Write>(w: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    w.write_all(&(data.len() as u32).to_be_bytes())?;
+    w.write_all(kind)?;
+    w.write_all(data)?;
+
+    let crc = crc32(kind.iter().chain(data));
+    w.write_all(&crc.to_be_bytes())
+}
+
+/// Wrap `data` in a zlib stream made of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = u16::MAX as usize;
+
+    let mut out = Vec::with_capacity(data.len() + data.len() / MAX_BLOCK * 5 + 11);
+    out.extend_from_slice(&[0x78, 0x01]);
+
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+
+    if blocks.peek().is_none() {
+        // an empty stream still needs one final block
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+
+        out.push(last as u8);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    let mut crc = !0u32;
+
+    for &b in bytes {
+        crc ^= b as u32;
+
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+
+    let (mut a, mut b) = (1u32, 0u32);
+
+    for &byte in bytes {
+        a = (a + byte as u32) % MOD;
+        b = (b + a) % MOD;
+    }
+
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn grid_to_ppm_with_scale() {
+        let grid = Grid::from_rows(vec![vec!['#', '.']]);
+        let image = Image::from_grid(&grid, 2, |_, &c| if c == '#' { WHITE } else { BLACK });
+
+        assert_eq!(image.width(), 4);
+        assert_eq!(image.height(), 2);
+        assert_eq!(image.pixel(1, 1), Some(WHITE));
+        assert_eq!(image.pixel(2, 0), Some(BLACK));
+        assert_eq!(image.pixel(4, 0), None);
+
+        let mut ppm = Vec::new();
+        image.write_ppm(&mut ppm).unwrap();
+        assert!(ppm.starts_with(b"P6\n4 2\n255\n"));
+        assert_eq!(ppm.len(), 11 + 4 * 2 * 3);
+        assert_eq!(&ppm[11..17], &[255, 255, 255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn points_are_offset_to_bounding_box() {
+        let image = Image::from_points([Point::new(-2, 5), Point::new(0, 6)], 1, WHITE, BLACK);
+
+        assert_eq!((image.width(), image.height()), (3, 2));
+        assert_eq!(image.pixel(0, 0), Some(WHITE));
+        assert_eq!(image.pixel(2, 1), Some(WHITE));
+        assert_eq!(image.pixel(1, 0), Some(BLACK));
+    }
+
+    #[test]
+    fn heatmap_spans_palette() {
+        let grid = Grid::from_rows(vec![vec![0u32, 2, 4]]);
+        let image = Image::heatmap(&grid, 1, |&v| v.into());
+
+        assert_eq!(image.pixel(0, 0), Some([0, 0, 255]));
+        assert_eq!(image.pixel(1, 0), Some([0, 255, 0]));
+        assert_eq!(image.pixel(2, 0), Some([255, 0, 0]));
+    }
+
+    #[test]
+    fn heatmap_of_usize_grid() {
+        let grid: Grid<usize> = Grid::from_rows(vec![vec![10, 20], vec![30, 10]]);
+        let image = Image::heatmap(&grid, 2, |&v| v as f64);
+
+        assert_eq!((image.width(), image.height()), (4, 4));
+        assert_eq!(image.pixel(1, 1), Some([0, 0, 255]));
+        assert_eq!(image.pixel(2, 0), Some([0, 255, 0]));
+        assert_eq!(image.pixel(0, 3), Some([255, 0, 0]));
+    }
+
+    #[test]
+    fn png_structure() {
+        let image = Image::new(2, 1, [1, 2, 3]);
+        let mut png = Vec::new();
+        image.write_png(&mut png).unwrap();
+
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 2, 0, 0, 0, 1]);
+        assert_eq!(&png[png.len() - 8..], b"IEND\xae\x42\x60\x82");
+
+        // IDAT holds a zlib header, one final stored block, the filtered
+        // scanline and the adler32 checksum
+        let idat = 8 + 25;
+        assert_eq!(&png[idat + 4..idat + 8], b"IDAT");
+        let data = &png[idat + 8..];
+        assert_eq!(&data[..2], &[0x78, 0x01]);
+        assert_eq!(&data[2..7], &[1, 7, 0, !7, 0xff]);
+        assert_eq!(&data[7..14], &[0, 1, 2, 3, 1, 2, 3]);
+        assert_eq!(
+            &data[14..18],
+            &adler32(&[0, 1, 2, 3, 1, 2, 3]).to_be_bytes()
+        );
+    }
+}
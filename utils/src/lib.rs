@@ -1,5 +1,11 @@
+pub mod automaton;
+pub mod geometry;
 pub mod graph;
 pub mod grid;
+pub mod hex;
+pub mod image;
+pub mod metric;
+pub mod spatial;
 
 /// Loads the input file for the specified event, quest, and part
 /// from the inputs directory in the workspace root.
@@ -0,0 +1,187 @@
+//! Ways of measuring the distance between two points.
+//!
+//! Each metric is a unit struct implementing `Metric` for the point types it
+//! makes sense for, so code which needs a distance can take the metric as a
+//! type parameter and have it swapped without a rewrite. All distances are
+//! `u64` regardless of the coordinate type, saturating at `u64::MAX` for
+//! points too far apart to measure.
+
+use crate::grid::{Coord, Point2, Point3};
+use crate::hex::Hex;
+
+/// A distance function between points of type `P`.
+pub trait Metric<P> {
+    /// The distance between `a` and `b`.
+    fn distance(&self, a: P, b: P) -> u64;
+
+    /// The furthest two points can be apart along any single axis while
+    /// still being within `radius` of each other. Useful for narrowing down a
+    /// search to a bounding box before checking the real distance.
+    fn axis_reach(&self, radius: u64) -> u64;
+}
+
+/// The number of orthogonal steps between two points, i.e. `|dx| + |dy|`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Manhattan;
+
+/// The number of steps between two points when diagonal moves are allowed,
+/// i.e. the largest difference along any axis.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Chebyshev;
+
+/// The straight line distance squared, which keeps everything in integers.
+/// Remember to square any radius compared against it.
+///
+/// Squaring overflows `u64` once points are around 2^32 apart, so distances
+/// that large all saturate to `u64::MAX`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SquaredEuclidean;
+
+/// The number of steps between two cells of a hex grid.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct HexSteps;
+
+impl<C: Coord> Metric<Point2<C>> for Manhattan {
+    fn distance(&self, a: Point2<C>, b: Point2<C>) -> u64 {
+        a.manhattan_distance(b)
+    }
+
+    fn axis_reach(&self, radius: u64) -> u64 {
+        radius
+    }
+}
+
+impl<C: Coord> Metric<Point3<C>> for Manhattan {
+    fn distance(&self, a: Point3<C>, b: Point3<C>) -> u64 {
+        a.manhattan_distance(b)
+    }
+
+    fn axis_reach(&self, radius: u64) -> u64 {
+        radius
+    }
+}
+
+impl<C: Coord> Metric<Point2<C>> for Chebyshev {
+    fn distance(&self, a: Point2<C>, b: Point2<C>) -> u64 {
+        a.x.abs_diff_u64(b.x).max(a.y.abs_diff_u64(b.y))
+    }
+
+    fn axis_reach(&self, radius: u64) -> u64 {
+        radius
+    }
+}
+
+impl<C: Coord> Metric<Point3<C>> for Chebyshev {
+    fn distance(&self, a: Point3<C>, b: Point3<C>) -> u64 {
+        a.chebyshev_distance(b)
+    }
+
+    fn axis_reach(&self, radius: u64) -> u64 {
+        radius
+    }
+}
+
+impl<C: Coord> Metric<Point2<C>> for SquaredEuclidean {
+    fn distance(&self, a: Point2<C>, b: Point2<C>) -> u64 {
+        let (dx, dy) = (a.x.abs_diff_u64(b.x), a.y.abs_diff_u64(b.y));
+        dx.saturating_mul(dx).saturating_add(dy.saturating_mul(dy))
+    }
+
+    fn axis_reach(&self, radius: u64) -> u64 {
+        radius.isqrt()
+    }
+}
+
+impl<C: Coord> Metric<Point3<C>> for SquaredEuclidean {
+    fn distance(&self, a: Point3<C>, b: Point3<C>) -> u64 {
+        let (dx, dy, dz) = (
+            a.x.abs_diff_u64(b.x),
+            a.y.abs_diff_u64(b.y),
+            a.z.abs_diff_u64(b.z),
+        );
+        dx.saturating_mul(dx)
+            .saturating_add(dy.saturating_mul(dy))
+            .saturating_add(dz.saturating_mul(dz))
+    }
+
+    fn axis_reach(&self, radius: u64) -> u64 {
+        radius.isqrt()
+    }
+}
+
+impl Metric<Hex> for HexSteps {
+    fn distance(&self, a: Hex, b: Hex) -> u64 {
+        a.distance(b) as u64
+    }
+
+    fn axis_reach(&self, radius: u64) -> u64 {
+        radius
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Point, Point3D};
+
+    #[test]
+    fn metrics_2d() {
+        let (a, b) = (Point::new(1, 2), Point::new(-3, 5));
+
+        assert_eq!(Manhattan.distance(a, b), 7);
+        assert_eq!(Chebyshev.distance(a, b), 4);
+        assert_eq!(SquaredEuclidean.distance(a, b), 25);
+
+        let far = Point2::<i64>::new(i64::MIN, 0);
+        assert_eq!(Manhattan.distance(far, Point2::new(i64::MAX, 0)), u64::MAX);
+        assert_eq!(Manhattan.distance(far, Point2::new(i64::MAX, 1)), u64::MAX);
+    }
+
+    #[test]
+    fn large_distances_saturate() {
+        let origin = Point2::<i64>::zero();
+        let edge = Point2::<i64>::new(1 << 32, 0);
+        assert_eq!(
+            SquaredEuclidean.distance(origin, Point2::new(1 << 31, 1 << 31)),
+            1 << 63
+        );
+        assert_eq!(SquaredEuclidean.distance(origin, edge), u64::MAX);
+        assert_eq!(
+            SquaredEuclidean.distance(
+                Point2::new(i64::MIN, i64::MIN),
+                Point2::new(i64::MAX, i64::MAX)
+            ),
+            u64::MAX
+        );
+
+        let corner = Point3::<i64>::new(i64::MIN, i64::MIN, i64::MIN);
+        let opposite = Point3::new(i64::MAX, i64::MAX, i64::MAX);
+        assert_eq!(Manhattan.distance(corner, opposite), u64::MAX);
+        assert_eq!(SquaredEuclidean.distance(corner, opposite), u64::MAX);
+        assert_eq!(Chebyshev.distance(corner, opposite), u64::MAX);
+    }
+
+    #[test]
+    fn metrics_3d_and_hex() {
+        let (a, b) = (Point3D::new(0, 0, 0), Point3D::new(1, -2, 2));
+
+        assert_eq!(Manhattan.distance(a, b), 5);
+        assert_eq!(Chebyshev.distance(a, b), 2);
+        assert_eq!(SquaredEuclidean.distance(a, b), 9);
+        assert_eq!(
+            HexSteps.distance(Hex { q: 0, r: 0 }, Hex { q: 2, r: -1 }),
+            2
+        );
+    }
+
+    #[test]
+    fn axis_reach_bounds_the_distance() {
+        let origin = Point::new(0, 0);
+
+        for radius in 0..30 {
+            let reach = Metric::<Point>::axis_reach(&SquaredEuclidean, radius) as i32;
+            assert!(SquaredEuclidean.distance(origin, Point::new(reach, 0)) <= radius);
+            assert!(SquaredEuclidean.distance(origin, Point::new(reach + 1, 0)) > radius);
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! A spatial index for finding points near a given position.
+//!
+//! Points are kept in a hash of fixed size cubic buckets, so a query only has
+//! to look in the buckets overlapping the box it could possibly reach rather
+//! than scanning every point. Which points count as near is decided by any
+//! `Metric`.
+
+use crate::grid::{Point, Point3D};
+use crate::metric::Metric;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A point type which can be stored in a `SpatialIndex`.
+pub trait Locatable: Copy + Eq + Hash {
+    /// The coordinates as `[x, y, z]`, with `z` zero for 2D points.
+    fn coords(&self) -> [i64; 3];
+}
+
+impl Locatable for Point {
+    fn coords(&self) -> [i64; 3] {
+        [self.x as i64, self.y as i64, 0]
+    }
+}
+
+impl Locatable for Point3D {
+    fn coords(&self) -> [i64; 3] {
+        [self.x as i64, self.y as i64, self.z as i64]
+    }
+}
+
+/// A set of points bucketed by position for fast radius and nearest
+/// neighbour queries.
+///
+/// The bucket size should be around the radius usually searched for, so most
+/// queries only look at a handful of buckets.
+#[derive(Clone, Debug)]
+pub struct SpatialIndex<P> {
+    bucket_size: i64,
+    buckets: HashMap<[i64; 3], Vec<P>>,
+    len: usize,
+}
+
+impl<P: Locatable> SpatialIndex<P> {
+    /// Create an empty index with buckets `bucket_size` wide along each axis.
+    pub fn new(bucket_size: u32) -> Self {
+        assert!(bucket_size > 0, "Buckets must have a size");
+
+        Self {
+            bucket_size: bucket_size as i64,
+            buckets: HashMap::new(),
+            len: 0,
+        }
+    }
+
+    /// Number of points in the index.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no points in the index.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Add a point, returning false if it was already there.
+    pub fn insert(&mut self, p: P) -> bool {
+        let bucket = self.buckets.entry(self.key(p.coords())).or_default();
+
+        if bucket.contains(&p) {
+            return false;
+        }
+
+        bucket.push(p);
+        self.len += 1;
+        true
+    }
+
+    /// Remove a point, returning false if it wasn't there.
+    pub fn remove(&mut self, p: P) -> bool {
+        let key = self.key(p.coords());
+
+        let Some(bucket) = self.buckets.get_mut(&key) else {
+            return false;
+        };
+
+        let Some(i) = bucket.iter().position(|&q| q == p) else {
+            return false;
+        };
+
+        bucket.swap_remove(i);
+        self.len -= 1;
+
+        if bucket.is_empty() {
+            self.buckets.remove(&key);
+        }
+
+        true
+    }
+
+    /// Returns true if the point is in the index.
+    pub fn contains(&self, p: P) -> bool {
+        self.buckets
+            .get(&self.key(p.coords()))
+            .is_some_and(|bucket| bucket.contains(&p))
+    }
+
+    /// Iterate over every point in the index, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = P> + '_ {
+        self.buckets.values().flatten().copied()
+    }
+
+    /// Every point within `radius` of `p` as measured by `metric`, including
+    /// `p` itself if it's in the index.
+    pub fn within<M: Metric<P>>(&self, p: P, radius: u64, metric: M) -> Vec<P> {
+        self.candidates(p, metric.axis_reach(radius))
+            .filter(|&q| metric.distance(p, q) <= radius)
+            .collect()
+    }
+
+    /// The closest point to `p` as measured by `metric`, which is `p` itself
+    /// if it's in the index. Ties are broken arbitrarily.
+    pub fn nearest<M: Metric<P>>(&self, p: P, metric: M) -> Option<P> {
+        let mut reach = self.bucket_size as u64;
+
+        loop {
+            let best = self
+                .candidates(p, reach)
+                .map(|q| (metric.distance(p, q), q))
+                .min_by_key(|&(distance, _)| distance);
+
+            match best {
+                // anything closer would have been inside the box searched
+                Some((distance, q)) if metric.axis_reach(distance) <= reach => return Some(q),
+                _ if self.covers_everything(p, reach) => return best.map(|(_, q)| q),
+                _ => reach = reach.saturating_mul(2),
+            }
+        }
+    }
+
+    /// The points which could be within `reach` of `p` along every axis.
+    /// Falls back to every point once the box covers more buckets than exist.
+    fn candidates(&self, p: P, reach: u64) -> Box<dyn Iterator<Item = P> + '_> {
+        if self.covers_everything(p, reach) {
+            return Box::new(self.iter());
+        }
+
+        let (min, max) = self.key_range(p, reach);
+
+        Box::new(
+            (min[2]..=max[2])
+                .flat_map(move |z| (min[1]..=max[1]).map(move |y| (y, z)))
+                .flat_map(move |(y, z)| (min[0]..=max[0]).map(move |x| [x, y, z]))
+                .filter_map(|key| self.buckets.get(&key))
+                .flatten()
+                .filter(move |q| {
+                    let (a, b) = (p.coords(), q.coords());
+                    (0..3).all(|axis| a[axis].abs_diff(b[axis]) <= reach)
+                })
+                .copied(),
+        )
+    }
+
+    /// True when the box of buckets reachable from `p` holds more buckets than
+    /// are occupied, so it's quicker to just look at everything.
+    fn covers_everything(&self, p: P, reach: u64) -> bool {
+        let (min, max) = self.key_range(p, reach);
+
+        let count = (0..3).fold(1u64, |count, axis| {
+            count.saturating_mul(max[axis].abs_diff(min[axis]).saturating_add(1))
+        });
+
+        count >= self.buckets.len() as u64
+    }
+
+    /// The smallest and largest bucket keys within `reach` of `p` along each
+    /// axis.
+    fn key_range(&self, p: P, reach: u64) -> ([i64; 3], [i64; 3]) {
+        let reach = reach.min(i64::MAX as u64) as i64;
+        let c = p.coords();
+
+        (
+            self.key(c.map(|v| v.saturating_sub(reach))),
+            self.key(c.map(|v| v.saturating_add(reach))),
+        )
+    }
+
+    fn key(&self, coords: [i64; 3]) -> [i64; 3] {
+        coords.map(|v| v.div_euclid(self.bucket_size))
+    }
+}
+
+impl<P: Locatable> FromIterator<P> for SpatialIndex<P> {
+    /// Build an index with buckets of size 8 holding all the points.
+    fn from_iter<I: IntoIterator<Item = P>>(iter: I) -> Self {
+        let mut index = SpatialIndex::new(8);
+
+        for p in iter {
+            index.insert(p);
+        }
+
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::{Chebyshev, Manhattan, SquaredEuclidean};
+
+    fn scattered() -> Vec<Point> {
+        // a deterministic spread of points, some of them far out
+        (0..200)
+            .map(|i: i32| Point::new((i * 37) % 101 - 50, (i * 53) % 89 - 44))
+            .chain([Point::new(10_000, -10_000)])
+            .collect()
+    }
+
+    #[test]
+    fn insert_remove_contains() {
+        let mut index = SpatialIndex::new(4);
+
+        assert!(index.insert(Point::new(-3, 7)));
+        assert!(index.insert(Point::new(100, 7)));
+        assert!(!index.insert(Point::new(-3, 7)));
+        assert_eq!(index.len(), 2);
+
+        assert!(index.contains(Point::new(-3, 7)));
+        assert!(index.remove(Point::new(-3, 7)));
+        assert!(!index.remove(Point::new(-3, 7)));
+        assert!(!index.contains(Point::new(-3, 7)));
+        assert_eq!(index.iter().collect::<Vec<_>>(), vec![Point::new(100, 7)]);
+    }
+
+    #[test]
+    fn within_matches_brute_force() {
+        let points = scattered();
+        let mut index = SpatialIndex::new(5);
+        points.iter().for_each(|&p| _ = index.insert(p));
+
+        for &centre in &[
+            Point::new(0, 0),
+            Point::new(-50, 40),
+            Point::new(9_990, -9_990),
+        ] {
+            for radius in [0, 3, 12, 40] {
+                let mut found = index.within(centre, radius, Manhattan);
+                let mut expected: Vec<_> = points
+                    .iter()
+                    .copied()
+                    .filter(|&q| Manhattan.distance(centre, q) <= radius)
+                    .collect();
+
+                found.sort();
+                expected.sort();
+                expected.dedup();
+                assert_eq!(found, expected);
+            }
+        }
+
+        assert_eq!(
+            index.within(Point::new(0, 0), u64::MAX, Chebyshev).len(),
+            index.len()
+        );
+    }
+
+    #[test]
+    fn nearest_point() {
+        let points = scattered();
+        let index: SpatialIndex<Point> = points.iter().copied().collect();
+
+        for centre in [
+            Point::new(3, -2),
+            Point::new(-200, 300),
+            Point::new(5_000, -5_000),
+        ] {
+            let nearest = index.nearest(centre, SquaredEuclidean).unwrap();
+            let best = points
+                .iter()
+                .map(|&q| SquaredEuclidean.distance(centre, q))
+                .min()
+                .unwrap();
+
+            assert_eq!(SquaredEuclidean.distance(centre, nearest), best);
+        }
+
+        assert_eq!(
+            SpatialIndex::<Point>::new(3).nearest(Point::new(0, 0), Manhattan),
+            None
+        );
+    }
+
+    #[test]
+    fn points_in_3d() {
+        let index: SpatialIndex<Point3D> = (-5..=5)
+            .flat_map(|x| (-5..=5).map(move |y| Point3D::new(x, y, x - y)))
+            .collect();
+
+        let near = index.within(Point3D::new(0, 0, 0), 1, Chebyshev);
+        assert_eq!(near.len(), 7);
+        assert_eq!(
+            index.nearest(Point3D::new(20, -20, 40), Manhattan),
+            Some(Point3D::new(5, -5, 10))
+        );
+    }
+}